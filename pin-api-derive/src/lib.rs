@@ -0,0 +1,218 @@
+//! Derive safe structural pin projections for `pin_api::mem::Pin`.
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate quote;
+#[macro_use]
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use syn::{Data, DeriveInput, Fields, GenericParam, Ident, Lifetime, LifetimeDef, Meta, NestedMeta};
+
+/// A type deriving `PinProject` can't implement `Drop` by hand:
+///
+/// ```compile_fail,E0119
+/// extern crate pin_api;
+/// #[macro_use]
+/// extern crate pin_api_derive;
+///
+/// #[derive(PinProject)]
+/// struct Foo {
+///     #[pin]
+///     field: u32,
+/// }
+///
+/// impl Drop for Foo {
+///     fn drop(&mut self) {}
+/// }
+/// # fn main() {}
+/// ```
+///
+/// The only argument `#[pin_project]` accepts is `PinnedDrop`:
+///
+/// ```compile_fail
+/// extern crate pin_api;
+/// #[macro_use]
+/// extern crate pin_api_derive;
+///
+/// #[derive(PinProject)]
+/// #[pin_project(NotPinnedDrop)]
+/// struct Foo {
+///     #[pin]
+///     field: u32,
+/// }
+/// # fn main() {}
+/// ```
+#[proc_macro_derive(PinProject, attributes(pin, pin_project))]
+pub fn derive_pin_project(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match pin_project(&input) {
+        Ok(tokens)  => tokens.into(),
+        Err(error)  => error.to_compile_error().into(),
+    }
+}
+
+fn pin_project(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    if is_packed(input)? {
+        return Err(syn::Error::new_spanned(&input.ident,
+            "#[derive(PinProject)] cannot be used on #[repr(packed)] types"));
+    }
+
+    let fields = match input.data {
+        Data::Struct(ref data)  => &data.fields,
+        _                       => return Err(syn::Error::new_spanned(&input.ident,
+            "#[derive(PinProject)] can only be used on structs")),
+    };
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let projection = Ident::new(&format!("{}Projection", name), Span::call_site());
+    let guard = Ident::new(&format!("__{}MustNotImplDrop", name), Span::call_site());
+
+    let lifetime = Lifetime::new("'__pin", Span::call_site());
+    let mut proj_generics = input.generics.clone();
+    proj_generics.params.insert(0, GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())));
+    {
+        let params: Vec<Ident> = input.generics.type_params().map(|param| param.ident.clone()).collect();
+        let where_clause = proj_generics.make_where_clause();
+        for param in params {
+            where_clause.predicates.push(parse_quote!(#param: #lifetime));
+        }
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let pinned_drop = has_pinned_drop(input)?;
+    let (_, proj_ty_generics, proj_where_clause) = proj_generics.split_for_impl();
+    let proj_params = &proj_generics.params;
+
+    let mut proj_fields = vec![];
+    let mut proj_values = vec![];
+    let mut bindings = vec![];
+    let mut unpin_bounds = vec![];
+
+    for (i, field) in fields.iter().enumerate() {
+        let binding = Ident::new(&format!("__field{}", i), Span::call_site());
+        let ty = &field.ty;
+        let field_vis = &field.vis;
+        let pinned = field.attrs.iter().any(|attr| attr.path.is_ident("pin"));
+
+        let (proj_ty, proj_value) = if pinned {
+            unpin_bounds.push(quote!(::pin_api::marker::__Wrapper<#lifetime, #ty>: ::pin_api::marker::Unpin));
+            (quote!(::pin_api::mem::Pin<#lifetime, #ty>),
+             quote!(unsafe { ::pin_api::mem::Pin::new_unchecked(#binding) }))
+        } else {
+            (quote!(&#lifetime mut #ty), quote!(#binding))
+        };
+
+        match field.ident {
+            Some(ref ident) => {
+                proj_fields.push(quote!(#field_vis #ident: #proj_ty));
+                proj_values.push(quote!(#ident: #proj_value));
+                bindings.push(quote!(#ident: ref mut #binding));
+            }
+            None            => {
+                let index = syn::Index::from(i);
+                proj_fields.push(quote!(#field_vis #proj_ty));
+                proj_values.push(quote!(#index: #proj_value));
+                bindings.push(quote!(#index: ref mut #binding));
+            }
+        }
+    }
+
+    let proj_struct = match *fields {
+        Fields::Named(_)    => quote! {
+            #vis struct #projection<#proj_params> #proj_where_clause {
+                #(#proj_fields,)*
+            }
+        },
+        _                   => quote! {
+            #vis struct #projection<#proj_params>(#(#proj_fields,)*) #proj_where_clause;
+        },
+    };
+
+    // The Unpin impl is bounded only on the pinned fields. Wrapping each field type
+    // with an extra lifetime keeps the bound from being trivially true or false when
+    // the pinned fields have concrete types.
+    let mut unpin_generics = input.generics.clone();
+    unpin_generics.params.insert(0, GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())));
+    {
+        let where_clause = unpin_generics.make_where_clause();
+        for bound in unpin_bounds {
+            where_clause.predicates.push(parse_quote!(#bound));
+        }
+    }
+    let (unpin_impl_generics, _, unpin_where) = unpin_generics.split_for_impl();
+
+    // Either the type gets a Drop impl forwarding to PinnedDrop, or it must not
+    // implement Drop at all. In both cases a hand-written Drop impl will conflict.
+    let drop_impl = if pinned_drop {
+        quote! {
+            impl #impl_generics Drop for #name #ty_generics #where_clause {
+                fn drop(&mut self) {
+                    unsafe { ::pin_api::mem::PinnedDrop::drop(::pin_api::mem::Pin::new_unchecked(self)) }
+                }
+            }
+        }
+    } else {
+        quote! {
+            #[allow(non_camel_case_types)]
+            trait #guard {}
+            #[allow(drop_bounds)]
+            impl<T: Drop> #guard for T {}
+            impl #impl_generics #guard for #name #ty_generics #where_clause {}
+        }
+    };
+
+    Ok(quote! {
+        #proj_struct
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[allow(dead_code)]
+            #vis fn project<#lifetime, '__a>(this: &#lifetime mut ::pin_api::mem::Pin<'__a, Self>)
+                -> #projection #proj_ty_generics
+            {
+                let &mut #name { #(#bindings,)* } = unsafe { ::pin_api::mem::Pin::get_mut(this) };
+                #projection { #(#proj_values,)* }
+            }
+        }
+
+        ::pin_api::__impl_unpin! {
+            #unpin_impl_generics ::pin_api::marker::Unpin for #name #ty_generics #unpin_where {}
+        }
+
+        #drop_impl
+    })
+}
+
+fn has_pinned_drop(input: &DeriveInput) -> syn::Result<bool> {
+    let mut pinned_drop = false;
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("pin_project")) {
+        match attr.parse_meta()? {
+            Meta::List(ref list)    => for nested in &list.nested {
+                match *nested {
+                    NestedMeta::Meta(Meta::Word(ref word)) if word == "PinnedDrop" => pinned_drop = true,
+                    _   => return Err(syn::Error::new_spanned(nested, "expected `PinnedDrop`")),
+                }
+            },
+            ref meta                => return Err(syn::Error::new_spanned(meta,
+                "expected #[pin_project(PinnedDrop)]")),
+        }
+    }
+    Ok(pinned_drop)
+}
+
+fn is_packed(input: &DeriveInput) -> syn::Result<bool> {
+    for attr in input.attrs.iter().filter(|attr| attr.path.is_ident("repr")) {
+        if let Meta::List(ref list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                match *nested {
+                    NestedMeta::Meta(Meta::Word(ref word)) if word == "packed"  => return Ok(true),
+                    NestedMeta::Meta(Meta::List(ref list)) if list.ident == "packed" => return Ok(true),
+                    _                                                           => {}
+                }
+            }
+        }
+    }
+    Ok(false)
+}
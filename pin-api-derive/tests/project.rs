@@ -0,0 +1,40 @@
+extern crate pin_api;
+#[macro_use]
+extern crate pin_api_derive;
+
+use pin_api::marker::Pinned;
+use pin_api::mem::{with_pinned, Pin};
+
+#[derive(PinProject)]
+struct Named {
+    #[pin]
+    pinned: (u32, Pinned),
+    unpinned: Vec<u32>,
+}
+
+#[derive(PinProject)]
+struct Tuple(#[pin] (u32, Pinned), u32);
+
+#[test]
+fn project_named() {
+    let named = Named { pinned: (1, Pinned), unpinned: vec![] };
+    with_pinned(named, |mut named| {
+        let addr = &named.pinned as *const (u32, Pinned);
+        {
+            let projection = Named::project(&mut named);
+            let pinned: Pin<(u32, Pinned)> = projection.pinned;
+            assert_eq!(&*pinned as *const (u32, Pinned), addr);
+            projection.unpinned.push(pinned.0);
+        }
+        assert_eq!(named.unpinned, vec![1]);
+    });
+}
+
+#[test]
+fn project_tuple() {
+    with_pinned(Tuple((2, Pinned), 3), |mut tuple| {
+        let projection = Tuple::project(&mut tuple);
+        *projection.1 += (projection.0).0;
+        assert_eq!(*projection.1, 5);
+    });
+}
@@ -0,0 +1,23 @@
+extern crate pin_api;
+#[macro_use]
+extern crate pin_api_derive;
+
+use pin_api::marker::Unpin;
+
+fn assert_unpin<T: Unpin>() {}
+
+#[derive(PinProject)]
+struct Generic<T, U> {
+    #[pin]
+    pinned: T,
+    unpinned: U,
+}
+
+#[derive(PinProject)]
+struct Concrete(#[pin] u32, String);
+
+#[test]
+fn unpin_follows_pinned_fields() {
+    assert_unpin::<Generic<u32, String>>();
+    assert_unpin::<Concrete>();
+}
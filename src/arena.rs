@@ -0,0 +1,109 @@
+use core::cell::RefCell;
+use core::fmt;
+use core::ptr;
+
+#[cfg(not(feature = "nightly"))]
+use marker::Unpin;
+use mem::Pin;
+
+const FIRST_CHUNK: usize = 8;
+
+pub struct PinArena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+impl<T> PinArena<T> {
+    pub fn new() -> PinArena<T> {
+        PinArena { chunks: RefCell::new(Vec::new()) }
+    }
+
+    pub fn alloc<'a>(&'a self, data: T) -> Pin<'a, T> {
+        let mut chunks = self.chunks.borrow_mut();
+
+        let full = match chunks.last() {
+            Some(chunk) => chunk.len() == chunk.capacity(),
+            None        => true,
+        };
+
+        if full {
+            let capacity = chunks.last().map_or(FIRST_CHUNK, |chunk| chunk.capacity() * 2);
+            chunks.push(Vec::with_capacity(capacity));
+        }
+
+        // Chunks are never grown past their capacity, so values don't move. Writing
+        // through the raw pointer avoids reborrowing values handed out earlier.
+        let chunk = chunks.last_mut().unwrap();
+        let len = chunk.len();
+        unsafe {
+            let ptr = chunk.as_mut_ptr().add(len);
+            ptr::write(ptr, data);
+            chunk.set_len(len + 1);
+            Pin::new_unchecked(&mut *ptr)
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(|chunk| chunk.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Default for PinArena<T> {
+    fn default() -> PinArena<T> {
+        PinArena::new()
+    }
+}
+
+impl<T> fmt::Debug for PinArena<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PinArena").field("len", &self.len()).finish()
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<T: Unpin> Unpin for PinArena<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use marker::Pinned;
+
+    use super::PinArena;
+
+    struct Counted<'a>(&'a Cell<usize>, Pinned);
+
+    impl<'a> Drop for Counted<'a> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn values_keep_their_address() {
+        let arena = PinArena::new();
+        let addrs: Vec<*const (usize, Pinned)> = (0..100usize).map(|i| {
+            &*arena.alloc((i, Pinned)) as *const (usize, Pinned)
+        }).collect();
+        assert_eq!(arena.len(), 100);
+        for (i, &addr) in addrs.iter().enumerate() {
+            assert_eq!(unsafe { (*addr).0 }, i);
+        }
+    }
+
+    #[test]
+    fn drops_values_with_the_arena() {
+        let drops = Cell::new(0);
+        {
+            let arena = PinArena::new();
+            for _ in 0..20 {
+                arena.alloc(Counted(&drops, Pinned));
+            }
+            assert_eq!(drops.get(), 0);
+        }
+        assert_eq!(drops.get(), 20);
+    }
+}
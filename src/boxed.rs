@@ -1,11 +1,18 @@
+use core::convert::Infallible;
 use core::fmt;
+#[cfg(feature = "nightly")]
 use core::marker::Unsize;
-use core::ops::{CoerceUnsized, Deref, DerefMut};
+use core::mem::MaybeUninit;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+#[cfg(feature = "nightly")]
+use core::ops::CoerceUnsized;
 
+use init::PinInit;
 use marker::Unpin;
-use mem::Pin;
+use mem::{Pin, PinRef};
 
-#[fundamental]
+#[cfg_attr(feature = "nightly", fundamental)]
 pub struct PinBox<T: ?Sized> {
     inner: Box<T>,
 }
@@ -14,6 +21,41 @@ impl<T> PinBox<T> {
     pub fn new(data: T) -> PinBox<T> {
         PinBox { inner: Box::new(data) }
     }
+
+    pub fn pin_init<I: PinInit<T, Error = Infallible>>(init: I) -> PinBox<T> {
+        match PinBox::try_pin_init(init) {
+            Ok(boxed)   => boxed,
+            Err(never)  => match never {},
+        }
+    }
+
+    pub fn try_pin_init<I: PinInit<T>>(init: I) -> Result<PinBox<T>, I::Error> {
+        PinBox::init_slot(Box::new(MaybeUninit::uninit()), init)
+    }
+
+    pub fn set(this: &mut PinBox<T>, value: T) {
+        *this.inner = value;
+    }
+
+    pub fn overwrite<I: PinInit<T, Error = Infallible>>(this: PinBox<T>, init: I) -> PinBox<T> {
+        match PinBox::try_overwrite(this, init) {
+            Ok(boxed)   => boxed,
+            Err(never)  => match never {},
+        }
+    }
+
+    // If initialization fails, the allocation is freed.
+    pub fn try_overwrite<I: PinInit<T>>(this: PinBox<T>, init: I) -> Result<PinBox<T>, I::Error> {
+        let raw = Box::into_raw(this.inner);
+        let slot = unsafe { Box::from_raw(raw as *mut MaybeUninit<T>) };
+        unsafe { ptr::drop_in_place(raw) };
+        PinBox::init_slot(slot, init)
+    }
+
+    fn init_slot<I: PinInit<T>>(mut slot: Box<MaybeUninit<T>>, init: I) -> Result<PinBox<T>, I::Error> {
+        init.init(unsafe { Pin::new_unchecked(&mut *slot) })?;
+        Ok(PinBox { inner: unsafe { Box::from_raw(Box::into_raw(slot) as *mut T) } })
+    }
 }
 
 impl<T: ?Sized> PinBox<T> {
@@ -21,13 +63,41 @@ impl<T: ?Sized> PinBox<T> {
         unsafe { Pin::new_unchecked(&mut *self.inner) }
     }
 
-    pub unsafe fn get_mut<'a>(this: &'a mut PinBox<T>) -> &'a mut T {
-        &mut *this.inner
+    pub fn as_pin_ref<'a>(&'a self) -> PinRef<'a, T> {
+        unsafe { PinRef::new_unchecked(&*self.inner) }
+    }
+
+    /// # Safety
+    ///
+    /// The value must not be moved out of the returned reference.
+    pub unsafe fn get_mut(this: &mut PinBox<T>) -> &mut T {
+        &mut this.inner
     }
 
+    /// # Safety
+    ///
+    /// Unless `T` is `Unpin`, the value must not be moved out of the returned
+    /// box.
     pub unsafe fn unpin(this: PinBox<T>) -> Box<T> {
         this.inner
     }
+
+    #[doc(hidden)]
+    pub unsafe fn __into_dyn<U, F>(this: PinBox<T>, f: F) -> PinBox<U> where
+        U: ?Sized,
+        F: FnOnce(*mut T) -> *mut U,
+    {
+        PinBox { inner: Box::from_raw(f(Box::into_raw(this.inner))) }
+    }
+}
+
+#[macro_export]
+macro_rules! pin_box_into_dyn {
+    ($boxed:expr => $t:ty) => {
+        match $boxed {
+            boxed => unsafe { $crate::boxed::PinBox::__into_dyn(boxed, |ptr| -> *mut $t { ptr }) },
+        }
+    }
 }
 
 impl<T: ?Sized> From<Box<T>> for PinBox<T> {
@@ -36,6 +106,7 @@ impl<T: ?Sized> From<Box<T>> for PinBox<T> {
     }
 }
 
+#[cfg(feature = "nightly")]
 #[allow(incoherent_fundamental_impls)]
 impl<T: Unpin + ?Sized> Into<Box<T>> for PinBox<T> {
     fn into(self) -> Box<T> {
@@ -47,13 +118,13 @@ impl<T: ?Sized> Deref for PinBox<T> {
     type Target = T;
 
     fn deref(&self) -> &T {
-        &*self.inner
+        &self.inner
     }
 }
 
 impl<T: Unpin + ?Sized> DerefMut for PinBox<T> {
     fn deref_mut(&mut self) -> &mut T {
-        &mut *self.inner
+        &mut self.inner
     }
 }
 
@@ -76,4 +147,63 @@ impl<T: ?Sized> fmt::Pointer for PinBox<T> {
     }
 }
 
+#[cfg(feature = "nightly")]
 impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<PinBox<U>> for PinBox<T> {}
+
+#[cfg(feature = "nightly")]
+impl<T: ?Sized> Unpin for PinBox<T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<T: ?Sized> Unpin for PinBox<T> {}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use init;
+    use marker::Pinned;
+
+    use super::PinBox;
+
+    struct Counted(Rc<Cell<usize>>, Pinned);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn set_replaces_in_place() {
+        let drops = Rc::new(Cell::new(0));
+        let mut boxed = PinBox::new(Counted(drops.clone(), Pinned));
+        let addr = &*boxed as *const Counted;
+        PinBox::set(&mut boxed, Counted(drops.clone(), Pinned));
+        assert_eq!(drops.get(), 1);
+        assert_eq!(&*boxed as *const Counted, addr);
+    }
+
+    #[test]
+    fn overwrite_reuses_the_allocation() {
+        let drops = Rc::new(Cell::new(0));
+        let boxed = PinBox::new(Counted(drops.clone(), Pinned));
+        let addr = &*boxed as *const Counted;
+        let boxed = PinBox::overwrite(boxed, init::value(Counted(drops.clone(), Pinned)));
+        assert_eq!(drops.get(), 1);
+        assert_eq!(&*boxed as *const Counted, addr);
+        drop(boxed);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn pin_box_into_dyn() {
+        use std::fmt::Debug;
+
+        let boxed = PinBox::new((7u32, Pinned));
+        let addr = &*boxed as *const (u32, Pinned) as *const ();
+        let dynamic = pin_box_into_dyn!(boxed => dyn Debug);
+        assert_eq!(&*dynamic as *const dyn Debug as *const (), addr);
+        assert_eq!(format!("{:?}", dynamic), "(7, Pinned)");
+    }
+}
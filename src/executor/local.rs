@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering::{Acquire, Release};
+use std::thread::{self, Thread};
+
+use boxed::PinBox;
+use future::Future;
+use mem::Pin;
+use slab::{Key, PinSlab};
+use task::{self, Context, Poll, Wake};
+
+struct ThreadNotify {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+impl ThreadNotify {
+    fn current() -> ThreadNotify {
+        ThreadNotify { thread: thread::current(), woken: AtomicBool::new(false) }
+    }
+
+    fn park(&self) {
+        while !self.woken.swap(false, Acquire) {
+            thread::park();
+        }
+    }
+}
+
+impl Wake for ThreadNotify {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Release);
+        self.thread.unpark();
+    }
+}
+
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    pin_mut!(future);
+    let notify = Arc::new(ThreadNotify::current());
+    let waker = task::waker(notify.clone());
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(output) = Pin::borrow(&mut future).poll(&mut cx) {
+            return output
+        }
+        notify.park();
+    }
+}
+
+struct Ready {
+    queue: Mutex<VecDeque<Key>>,
+    notify: Arc<ThreadNotify>,
+}
+
+struct TaskWaker {
+    key: Key,
+    ready: Arc<Ready>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.ready.queue.lock().unwrap().push_back(self.key);
+        self.ready.notify.wake_by_ref();
+    }
+}
+
+// Tasks are only polled after their waker has queued their key. Keys of
+// finished tasks are stale in the slab, so late wakeups are ignored.
+pub struct LocalPool {
+    tasks: PinSlab<PinBox<dyn Future<Output = ()>>>,
+    ready: Arc<Ready>,
+}
+
+impl LocalPool {
+    pub fn new() -> LocalPool {
+        LocalPool {
+            tasks: PinSlab::new(),
+            ready: Arc::new(Ready {
+                queue: Mutex::new(VecDeque::new()),
+                notify: Arc::new(ThreadNotify::current()),
+            }),
+        }
+    }
+
+    pub fn spawn_local<F: Future<Output = ()> + 'static>(&mut self, future: F) {
+        let key = self.tasks.insert(PinBox::new(future));
+        self.ready.queue.lock().unwrap().push_back(key);
+    }
+
+    pub fn run_until_stalled(&mut self) {
+        loop {
+            let key = match self.ready.queue.lock().unwrap().pop_front() {
+                Some(key)   => key,
+                None        => return,
+            };
+
+            let waker = task::waker(Arc::new(TaskWaker { key, ready: self.ready.clone() }));
+            let mut cx = Context::from_waker(&waker);
+
+            let done = match self.tasks.get_pin(key) {
+                Some(mut task)  => task.as_pin().poll(&mut cx).is_ready(),
+                None            => false,
+            };
+
+            if done {
+                self.tasks.remove(key);
+            }
+        }
+    }
+
+    pub fn run(&mut self) {
+        loop {
+            self.run_until_stalled();
+            if self.tasks.is_empty() { return }
+            self.ready.notify.park();
+        }
+    }
+
+    pub fn run_until<F: Future>(&mut self, future: F) -> F::Output {
+        pin_mut!(future);
+        let waker = task::waker(self.ready.notify.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        loop {
+            if let Poll::Ready(output) = Pin::borrow(&mut future).poll(&mut cx) {
+                return output
+            }
+            self.run_until_stalled();
+            if self.ready.queue.lock().unwrap().is_empty() {
+                self.ready.notify.park();
+            }
+        }
+    }
+}
+
+impl Default for LocalPool {
+    fn default() -> LocalPool {
+        LocalPool::new()
+    }
+}
+
+impl fmt::Debug for LocalPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LocalPool").field("tasks", &self.tasks.len()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use executor::test_futures::{send_later, YieldNow};
+    use future::FutureExt;
+
+    use super::{block_on, LocalPool};
+
+    #[test]
+    fn block_on_polls_until_ready() {
+        assert_eq!(block_on(YieldNow(1).map(|()| 1)), 1);
+    }
+
+    #[test]
+    fn block_on_is_woken_from_another_thread() {
+        assert_eq!(block_on(send_later(2)), 2);
+    }
+
+    #[test]
+    fn local_pool_runs_tasks_to_completion() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut pool = LocalPool::new();
+        for i in 0..3 {
+            let log = log.clone();
+            pool.spawn_local(YieldNow(1).map(move |()| log.borrow_mut().push(i)));
+        }
+        assert!(log.borrow().is_empty());
+
+        pool.run_until_stalled();
+        assert_eq!(*log.borrow(), [0, 1, 2]);
+        assert!(pool.tasks.is_empty());
+    }
+
+    #[test]
+    fn local_pool_waits_for_foreign_wakeups() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let mut pool = LocalPool::new();
+        let task_log = log.clone();
+        pool.spawn_local(send_later(3).map(move |value| task_log.borrow_mut().push(value)));
+        pool.run();
+        assert_eq!(*log.borrow(), [3]);
+
+        assert_eq!(pool.run_until(send_later(4)), 4);
+    }
+}
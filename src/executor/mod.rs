@@ -0,0 +1,8 @@
+//! Executors for pinned futures.
+mod local;
+mod thread_pool;
+#[cfg(test)]
+pub(crate) mod test_futures;
+
+pub use self::local::{block_on, LocalPool};
+pub use self::thread_pool::{JoinError, JoinHandle, ThreadPool};
@@ -0,0 +1,56 @@
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use future::Future;
+use mem::Pin;
+use task::{Context, Poll, Waker};
+
+// Returns `Pending` the given number of times, waking itself each time.
+pub struct YieldNow(pub u32);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 == 0 { return Poll::Ready(()) }
+        self.0 -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+// Resolves once another thread has sent a value.
+pub struct Recv(Arc<Mutex<(Option<u32>, Option<Waker>)>>);
+
+impl Future for Recv {
+    type Output = u32;
+
+    fn poll(self: Pin<Self>, cx: &mut Context) -> Poll<u32> {
+        let mut slot = self.0.lock().unwrap();
+        match slot.0.take() {
+            Some(value) => Poll::Ready(value),
+            None        => {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+pub fn send_later(value: u32) -> Recv {
+    let slot = Arc::new(Mutex::new((None, None::<Waker>)));
+    let sender = slot.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        let waker = {
+            let mut slot = sender.lock().unwrap();
+            slot.0 = Some(value);
+            slot.1.take()
+        };
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    });
+    Recv(slot)
+}
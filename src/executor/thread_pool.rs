@@ -0,0 +1,445 @@
+use std::cell::{Cell, UnsafeCell};
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::{AcqRel, Acquire, Release};
+use std::thread;
+
+use future::Future;
+use mem::Pin;
+use sync::{self, PinArc};
+use task::{self, Context, Poll, Wake, Waker};
+
+const IDLE: usize = 0;
+const SCHEDULED: usize = 1;
+const RUNNING: usize = 2;
+const NOTIFIED: usize = 3;
+const COMPLETE: usize = 4;
+
+type TaskRef = PinArc<dyn Run>;
+
+thread_local! {
+    // The pool and index of the worker running on this thread, if any.
+    static WORKER: Cell<Option<(*const Inner, usize)>> = Cell::new(None);
+}
+
+trait Run: Send + Sync {
+    fn state(&self) -> &AtomicUsize;
+    fn run(&self, this: &TaskRef, inner: &Arc<Inner>, worker: usize);
+    fn cancel(&self);
+}
+
+// A spawned future, pinned inside a `PinArc` for its whole life. The state
+// machine guarantees that only one thread touches the future at a time: the
+// thread that moves the task out of IDLE or SCHEDULED owns it.
+struct Task<F> {
+    state: AtomicUsize,
+    future: UnsafeCell<Option<F>>,
+}
+
+unsafe impl<F: Send> Sync for Task<F> {}
+
+impl<F: Future<Output = ()> + Send> Run for Task<F> {
+    fn state(&self) -> &AtomicUsize {
+        &self.state
+    }
+
+    fn run(&self, this: &TaskRef, inner: &Arc<Inner>, worker: usize) {
+        self.state.store(RUNNING, Release);
+
+        let waker = task::waker(Arc::new(TaskWaker { task: this.clone(), inner: inner.clone() }));
+        let mut cx = Context::from_waker(&waker);
+        let future = unsafe { &mut *self.future.get() };
+
+        // A panicking task is treated as finished; its join handle reports it.
+        let done = match *future {
+            Some(ref mut future)    => {
+                let future = unsafe { Pin::new_unchecked(future) };
+                panic::catch_unwind(AssertUnwindSafe(|| future.poll(&mut cx).is_ready()))
+                    .unwrap_or(true)
+            }
+            None                    => true,
+        };
+
+        if done {
+            *future = None;
+            self.state.store(COMPLETE, Release);
+        } else if self.state.compare_exchange(RUNNING, IDLE, AcqRel, Acquire).is_err() {
+            self.state.store(SCHEDULED, Release);
+            inner.locals[worker].lock().unwrap().push_back(this.clone());
+        }
+    }
+
+    fn cancel(&self) {
+        unsafe { *self.future.get() = None };
+        self.state.store(COMPLETE, Release);
+    }
+}
+
+struct TaskWaker {
+    task: TaskRef,
+    inner: Arc<Inner>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let state = self.task.state();
+        let mut current = state.load(Acquire);
+        loop {
+            let next = match current {
+                IDLE    => SCHEDULED,
+                RUNNING => NOTIFIED,
+                _       => return,
+            };
+            match state.compare_exchange(current, next, AcqRel, Acquire) {
+                Ok(IDLE)    => return self.inner.schedule(self.task.clone()),
+                Ok(_)       => return,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+}
+
+struct Shutdown {
+    requested: bool,
+    terminated: bool,
+}
+
+struct Inner {
+    global: Mutex<VecDeque<TaskRef>>,
+    locals: Vec<Mutex<VecDeque<TaskRef>>>,
+    tasks: Mutex<Vec<sync::Weak<dyn Run>>>,
+    shutdown: Mutex<Shutdown>,
+    condvar: Condvar,
+}
+
+impl Inner {
+    fn spawn(&self, task: TaskRef) {
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            if tasks.len() == tasks.capacity() {
+                tasks.retain(|task| task.upgrade().is_some());
+            }
+            tasks.push(PinArc::downgrade(&task));
+        }
+        self.schedule(task);
+    }
+
+    fn schedule(&self, task: TaskRef) {
+        let shutdown = self.shutdown.lock().unwrap();
+        if shutdown.terminated {
+            drop(shutdown);
+            return task.cancel()
+        }
+        match WORKER.with(|worker| worker.get()) {
+            Some((inner, worker)) if inner == self as *const Inner => {
+                self.locals[worker].lock().unwrap().push_back(task);
+            }
+            _ => self.global.lock().unwrap().push_back(task),
+        }
+        self.condvar.notify_one();
+    }
+
+    fn find_task(&self, worker: usize) -> Option<TaskRef> {
+        if let Some(task) = self.locals[worker].lock().unwrap().pop_front() {
+            return Some(task)
+        }
+
+        if let Some(task) = self.global.lock().unwrap().pop_front() {
+            return Some(task)
+        }
+
+        let count = self.locals.len();
+        (1..count).filter_map(|offset| {
+            self.locals[(worker + offset) % count].lock().unwrap().pop_back()
+        }).next()
+    }
+
+    fn has_work(&self) -> bool {
+        !self.global.lock().unwrap().is_empty()
+            || self.locals.iter().any(|local| !local.lock().unwrap().is_empty())
+    }
+
+    // Returns false once shutdown was requested and no work is left.
+    fn sleep(&self) -> bool {
+        let mut shutdown = self.shutdown.lock().unwrap();
+        loop {
+            if self.has_work() { return true }
+            if shutdown.requested { return false }
+            shutdown = self.condvar.wait(shutdown).unwrap();
+        }
+    }
+
+    fn work(inner: &Arc<Inner>, worker: usize) {
+        WORKER.with(|current| current.set(Some((&**inner as *const Inner, worker))));
+        loop {
+            match inner.find_task(worker) {
+                Some(task)  => task.run(&task, inner, worker),
+                None        => if !inner.sleep() { return },
+            }
+        }
+    }
+
+    // Drops every future that is still pending, which also releases the wakers
+    // those futures hold on to.
+    fn terminate(&self) {
+        self.shutdown.lock().unwrap().terminated = true;
+
+        let mut queued: Vec<TaskRef> = self.global.lock().unwrap().drain(..).collect();
+        for local in &self.locals {
+            queued.extend(local.lock().unwrap().drain(..));
+        }
+        for task in queued {
+            task.cancel();
+        }
+
+        let tasks: Vec<_> = self.tasks.lock().unwrap().drain(..).collect();
+        for task in tasks.iter().filter_map(|task| task.upgrade()) {
+            if task.state().compare_exchange(IDLE, COMPLETE, AcqRel, Acquire).is_ok() {
+                task.cancel();
+            }
+        }
+    }
+}
+
+pub struct ThreadPool {
+    inner: Arc<Inner>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    pub fn new() -> ThreadPool {
+        ThreadPool::with_threads(thread::available_parallelism().map_or(4, |count| count.get()))
+    }
+
+    pub fn with_threads(threads: usize) -> ThreadPool {
+        assert!(threads > 0, "a ThreadPool needs at least one thread");
+
+        let inner = Arc::new(Inner {
+            global: Mutex::new(VecDeque::new()),
+            locals: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+            tasks: Mutex::new(Vec::new()),
+            shutdown: Mutex::new(Shutdown { requested: false, terminated: false }),
+            condvar: Condvar::new(),
+        });
+
+        let workers = (0..threads).map(|worker| {
+            let inner = inner.clone();
+            thread::Builder::new()
+                .name(format!("pin-api-worker-{}", worker))
+                .spawn(move || Inner::work(&inner, worker))
+                .expect("failed to spawn worker thread")
+        }).collect();
+
+        ThreadPool { inner, workers }
+    }
+
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output> where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        let state = Arc::new(Mutex::new(JoinState { output: None, done: false, waker: None }));
+        let future = Spawned { future, sender: JoinSender { state: state.clone() } };
+        let task: TaskRef = PinArc::new(Task {
+            state: AtomicUsize::new(SCHEDULED),
+            future: UnsafeCell::new(Some(future)),
+        });
+        self.inner.spawn(task);
+        JoinHandle { state }
+    }
+
+    // Workers finish every task that is already scheduled before they exit;
+    // tasks still waiting on a wakeup are then dropped.
+    pub fn shutdown(mut self) {
+        self.shutdown_and_join()
+    }
+
+    fn shutdown_and_join(&mut self) {
+        self.inner.shutdown.lock().unwrap().requested = true;
+        self.inner.condvar.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+        self.inner.terminate();
+    }
+}
+
+impl Default for ThreadPool {
+    fn default() -> ThreadPool {
+        ThreadPool::new()
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        self.shutdown_and_join()
+    }
+}
+
+impl fmt::Debug for ThreadPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ThreadPool").field("threads", &self.workers.len()).finish()
+    }
+}
+
+struct JoinState<T> {
+    output: Option<T>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+struct JoinSender<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> JoinSender<T> {
+    fn send(&self, output: Option<T>) {
+        let mut state = self.state.lock().unwrap();
+        if state.done { return }
+        state.output = output;
+        state.done = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+// Runs if the task was cancelled or panicked before completing.
+impl<T> Drop for JoinSender<T> {
+    fn drop(&mut self) {
+        self.send(None)
+    }
+}
+
+struct Spawned<F: Future> {
+    future: F,
+    sender: JoinSender<F::Output>,
+}
+
+impl<F: Future> Spawned<F> {
+    unsafe_pinned!(future: F);
+    unsafe_unpinned!(sender: JoinSender<F::Output>);
+}
+
+impl<F: Future> Future for Spawned<F> {
+    type Output = ();
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<()> {
+        match self.future().poll(cx) {
+            Poll::Ready(output) => {
+                self.sender().send(Some(output));
+                Poll::Ready(())
+            }
+            Poll::Pending       => Poll::Pending,
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+pub struct JoinHandle<T> {
+    state: Arc<Mutex<JoinState<T>>>,
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(self: Pin<Self>, cx: &mut Context) -> Poll<Result<T, JoinError>> {
+        let mut state = self.state.lock().unwrap();
+        if !state.done {
+            state.waker = Some(cx.waker().clone());
+            return Poll::Pending
+        }
+        Poll::Ready(state.output.take().ok_or(JoinError))
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("JoinHandle")
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct JoinError;
+
+impl fmt::Display for JoinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("task was cancelled or panicked")
+    }
+}
+
+impl Error for JoinError {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::atomic::Ordering::SeqCst;
+
+    use executor::block_on;
+    use executor::test_futures::{send_later, YieldNow};
+    use future::{Future, FutureExt};
+    use mem::Pin;
+    use task::{Context, Poll};
+
+    use super::{JoinError, ThreadPool};
+
+    #[test]
+    fn spawn_and_join() {
+        let pool = ThreadPool::with_threads(2);
+        let handles: Vec<_> = (0..16).map(|i| {
+            pool.spawn(YieldNow(1).map(move |()| i * 2))
+        }).collect();
+        for (i, handle) in handles.into_iter().enumerate() {
+            assert_eq!(block_on(handle), Ok(i * 2));
+        }
+    }
+
+    #[test]
+    fn wakes_from_a_foreign_thread() {
+        let pool = ThreadPool::with_threads(1);
+        assert_eq!(block_on(pool.spawn(send_later(5))), Ok(5));
+    }
+
+    #[test]
+    fn panicking_task_reports_join_error() {
+        let pool = ThreadPool::with_threads(1);
+        let handle = pool.spawn(YieldNow(1).map(|()| -> u32 { panic!("task panicked") }));
+        assert_eq!(block_on(handle), Err(JoinError));
+        assert_eq!(block_on(pool.spawn(YieldNow(1))), Ok(()));
+    }
+
+    // Never completes and never registers its waker.
+    struct Pending(Arc<AtomicBool>);
+
+    impl Future for Pending {
+        type Output = ();
+
+        fn poll(self: Pin<Self>, _: &mut Context) -> Poll<()> {
+            Poll::Pending
+        }
+    }
+
+    impl Drop for Pending {
+        fn drop(&mut self) {
+            self.0.store(true, SeqCst);
+        }
+    }
+
+    #[test]
+    fn shutdown_drops_pending_tasks() {
+        let dropped = Arc::new(AtomicBool::new(false));
+        let pool = ThreadPool::with_threads(2);
+        let handle = pool.spawn(Pending(dropped.clone()));
+        pool.shutdown();
+        assert!(dropped.load(SeqCst));
+        assert_eq!(block_on(handle), Err(JoinError));
+    }
+}
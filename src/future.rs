@@ -0,0 +1,372 @@
+use core::mem;
+
+#[cfg(feature = "std")]
+use boxed::PinBox;
+use mem::Pin;
+use task::{Context, Poll};
+
+pub trait Future {
+    type Output;
+
+    fn poll(self: Pin<Self>, cx: &mut Context) -> Poll<Self::Output>;
+}
+
+impl<'a, F: Future + ?Sized> Future for Pin<'a, F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<F::Output> {
+        F::poll(Pin::borrow(&mut *self), cx)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: Future + ?Sized> Future for PinBox<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<F::Output> {
+        F::poll(self.as_pin(), cx)
+    }
+}
+
+pub trait FutureExt: Future {
+    fn map<U, F>(self, f: F) -> Map<Self, F> where
+        F: FnOnce(Self::Output) -> U,
+        Self: Sized,
+    {
+        Map { future: self, f: Some(f) }
+    }
+
+    fn then<Fut, F>(self, f: F) -> Then<Self, Fut, F> where
+        F: FnOnce(Self::Output) -> Fut,
+        Fut: Future,
+        Self: Sized,
+    {
+        Then { chain: Chain::First(self, Some(f)) }
+    }
+
+    fn and_then<T, E, Fut, F>(self, f: F) -> AndThen<Self, Fut, F> where
+        F: FnOnce(T) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        Self: Future<Output = Result<T, E>> + Sized,
+    {
+        AndThen { chain: Chain::First(self, Some(f)) }
+    }
+
+    fn join<Fut: Future>(self, other: Fut) -> Join<Self, Fut> where
+        Self: Sized,
+    {
+        Join { a: MaybeDone::Future(self), b: MaybeDone::Future(other) }
+    }
+
+    fn select<Fut>(self, other: Fut) -> Select<Self, Fut> where
+        Fut: Future<Output = Self::Output>,
+        Self: Sized,
+    {
+        Select { a: self, b: other }
+    }
+
+    #[cfg(feature = "std")]
+    fn boxed<'a>(self) -> PinBox<dyn Future<Output = Self::Output> + 'a> where
+        Self: Sized + 'a,
+    {
+        PinBox::new(self)
+    }
+}
+
+impl<F: Future + ?Sized> FutureExt for F {}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Map<Fut, F> {
+    future: Fut,
+    f: Option<F>,
+}
+
+impl<Fut, F> Map<Fut, F> {
+    unsafe_pinned!(future: Fut);
+    unsafe_unpinned!(f: Option<F>);
+}
+
+impl<U, Fut: Future, F: FnOnce(Fut::Output) -> U> Future for Map<Fut, F> {
+    type Output = U;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<U> {
+        match self.future().poll(cx) {
+            Poll::Ready(output) => {
+                let f = self.f().take().expect("Map polled after completion");
+                Poll::Ready(f(output))
+            }
+            Poll::Pending       => Poll::Pending,
+        }
+    }
+}
+
+// Two futures run one after another. The first is dropped in place before the
+// second is constructed, and neither is moved once the chain is pinned.
+#[derive(Debug)]
+enum Chain<Fut1, Fut2, Data> {
+    First(Fut1, Option<Data>),
+    Second(Fut2),
+    Empty,
+}
+
+impl<Fut1: Future, Fut2: Future, Data> Chain<Fut1, Fut2, Data> {
+    fn poll<F>(self: Pin<Self>, cx: &mut Context, f: F) -> Poll<Fut2::Output> where
+        F: FnOnce(Fut1::Output, Data) -> Result<Fut2, Fut2::Output>
+    {
+        let mut this = self;
+        let this = unsafe { Pin::get_mut(&mut this) };
+        let mut f = Some(f);
+
+        loop {
+            let (output, data) = match *this {
+                Chain::First(ref mut fut1, ref mut data) => {
+                    match unsafe { Pin::new_unchecked(fut1) }.poll(cx) {
+                        Poll::Ready(output) => (output, data.take().unwrap()),
+                        Poll::Pending       => return Poll::Pending,
+                    }
+                }
+                Chain::Second(ref mut fut2) => {
+                    return unsafe { Pin::new_unchecked(fut2) }.poll(cx)
+                }
+                Chain::Empty => panic!("future polled after completion"),
+            };
+
+            *this = Chain::Empty;
+            match (f.take().unwrap())(output, data) {
+                Ok(fut2)    => *this = Chain::Second(fut2),
+                Err(output) => return Poll::Ready(output),
+            }
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Then<Fut1, Fut2, F> {
+    chain: Chain<Fut1, Fut2, F>,
+}
+
+impl<Fut1, Fut2, F> Then<Fut1, Fut2, F> {
+    unsafe_pinned!(chain: Chain<Fut1, Fut2, F>);
+}
+
+impl<Fut1, Fut2, F> Future for Then<Fut1, Fut2, F> where
+    Fut1: Future,
+    Fut2: Future,
+    F: FnOnce(Fut1::Output) -> Fut2,
+{
+    type Output = Fut2::Output;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<Fut2::Output> {
+        self.chain().poll(cx, |output, f| Ok(f(output)))
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct AndThen<Fut1, Fut2, F> {
+    chain: Chain<Fut1, Fut2, F>,
+}
+
+impl<Fut1, Fut2, F> AndThen<Fut1, Fut2, F> {
+    unsafe_pinned!(chain: Chain<Fut1, Fut2, F>);
+}
+
+impl<T, U, E, Fut1, Fut2, F> Future for AndThen<Fut1, Fut2, F> where
+    Fut1: Future<Output = Result<T, E>>,
+    Fut2: Future<Output = Result<U, E>>,
+    F: FnOnce(T) -> Fut2,
+{
+    type Output = Result<U, E>;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<Result<U, E>> {
+        self.chain().poll(cx, |output, f| match output {
+            Ok(data)    => Ok(f(data)),
+            Err(error)  => Err(Err(error)),
+        })
+    }
+}
+
+enum MaybeDone<Fut: Future> {
+    Future(Fut),
+    Done(Fut::Output),
+    Gone,
+}
+
+impl<Fut: Future> MaybeDone<Fut> {
+    fn poll_done(self: Pin<Self>, cx: &mut Context) -> bool {
+        let mut this = self;
+        let this = unsafe { Pin::get_mut(&mut this) };
+        let output = match *this {
+            MaybeDone::Future(ref mut future) => {
+                match unsafe { Pin::new_unchecked(future) }.poll(cx) {
+                    Poll::Ready(output) => output,
+                    Poll::Pending       => return false,
+                }
+            }
+            MaybeDone::Done(_)  => return true,
+            MaybeDone::Gone     => panic!("Join polled after completion"),
+        };
+        *this = MaybeDone::Done(output);
+        true
+    }
+
+    // The output is never pinned, so it can be moved out once the future is gone.
+    fn take(self: Pin<Self>) -> Fut::Output {
+        let mut this = self;
+        let this = unsafe { Pin::get_mut(&mut this) };
+        match mem::replace(this, MaybeDone::Gone) {
+            MaybeDone::Done(output) => output,
+            _                       => unreachable!(),
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+pub struct Join<A: Future, B: Future> {
+    a: MaybeDone<A>,
+    b: MaybeDone<B>,
+}
+
+impl<A: Future, B: Future> Join<A, B> {
+    unsafe_pinned!(a: MaybeDone<A>);
+    unsafe_pinned!(b: MaybeDone<B>);
+}
+
+impl<A: Future, B: Future> Future for Join<A, B> {
+    type Output = (A::Output, B::Output);
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<(A::Output, B::Output)> {
+        let a_done = self.a().poll_done(cx);
+        let b_done = self.b().poll_done(cx);
+        if a_done && b_done {
+            Poll::Ready((self.a().take(), self.b().take()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Select<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A, B> Select<A, B> {
+    unsafe_pinned!(a: A);
+    unsafe_pinned!(b: B);
+}
+
+impl<A: Future, B: Future<Output = A::Output>> Future for Select<A, B> {
+    type Output = A::Output;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<A::Output> {
+        if let Poll::Ready(output) = self.a().poll(cx) {
+            return Poll::Ready(output)
+        }
+        self.b().poll(cx)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::rc::Rc;
+
+    use boxed::PinBox;
+    use executor::block_on;
+    use executor::test_futures::YieldNow;
+    use mem::Pin;
+    use task::{Context, Poll};
+
+    use super::{Future, FutureExt};
+
+    // Wraps a future and records when it is dropped.
+    struct Logged<F> {
+        future: F,
+        log: Rc<RefCell<Vec<&'static str>>>,
+    }
+
+    impl<F> Logged<F> {
+        unsafe_pinned!(future: F);
+    }
+
+    impl<F: Future> Future for Logged<F> {
+        type Output = F::Output;
+
+        fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<F::Output> {
+            self.future().poll(cx)
+        }
+    }
+
+    impl<F> Drop for Logged<F> {
+        fn drop(&mut self) {
+            self.log.borrow_mut().push("first dropped");
+        }
+    }
+
+    #[test]
+    fn map() {
+        assert_eq!(block_on(YieldNow(1).map(|()| 1)), 1);
+    }
+
+    #[test]
+    fn then_drops_the_first_future_before_building_the_second() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let first = Logged { future: YieldNow(1).map(|()| 1), log: log.clone() };
+        let second_log = log.clone();
+        let chained = first.then(move |x| {
+            second_log.borrow_mut().push("second built");
+            YieldNow(1).map(move |()| x + 1)
+        });
+        assert_eq!(block_on(chained), 2);
+        assert_eq!(*log.borrow(), ["first dropped", "second built"]);
+    }
+
+    #[test]
+    fn and_then_chains_ok_values() {
+        let chained = YieldNow(1).map(|()| Ok::<u32, &str>(1))
+            .and_then(|x| YieldNow(1).map(move |()| Ok(x + 1)));
+        assert_eq!(block_on(chained), Ok(2));
+    }
+
+    #[test]
+    fn and_then_stops_at_the_first_error() {
+        let called = Cell::new(false);
+        let chained = YieldNow(1).map(|()| Err::<u32, &str>("failed"))
+            .and_then(|x| {
+                called.set(true);
+                YieldNow(0).map(move |()| Ok(x))
+            });
+        assert_eq!(block_on(chained), Err("failed"));
+        assert!(!called.get());
+    }
+
+    #[test]
+    fn join_waits_for_both() {
+        let joined = YieldNow(3).map(|()| 'a').join(YieldNow(1).map(|()| 2));
+        assert_eq!(block_on(joined), ('a', 2));
+    }
+
+    #[test]
+    fn select_returns_the_first_ready_future() {
+        let slow_first = YieldNow(2).map(|()| "slow").select(YieldNow(0).map(|()| "fast"));
+        assert_eq!(block_on(slow_first), "fast");
+
+        let both_ready = YieldNow(0).map(|()| "a").select(YieldNow(0).map(|()| "b"));
+        assert_eq!(block_on(both_ready), "a");
+    }
+
+    #[test]
+    fn boxed_futures_dispatch_dynamically() {
+        let futures: Vec<PinBox<dyn Future<Output = u32>>> = vec![
+            YieldNow(1).map(|()| 1).boxed(),
+            YieldNow(2).map(|()| 2).join(YieldNow(0)).map(|(x, ())| x).boxed(),
+        ];
+        let outputs: Vec<u32> = futures.into_iter().map(block_on).collect();
+        assert_eq!(outputs, [1, 2]);
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "std")]
+use boxed::PinBox;
+use mem::Pin;
+use stream::Stream;
+use task::{Context, Poll};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GeneratorState<Y, R> {
+    Yielded(Y),
+    Complete(R),
+}
+
+pub trait Generator<Arg = ()> {
+    type Yield;
+    type Return;
+
+    fn resume(self: Pin<Self>, arg: Arg) -> GeneratorState<Self::Yield, Self::Return>;
+}
+
+impl<'a, Arg, G: Generator<Arg> + ?Sized> Generator<Arg> for Pin<'a, G> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(mut self: Pin<Self>, arg: Arg) -> GeneratorState<G::Yield, G::Return> {
+        G::resume(Pin::borrow(&mut *self), arg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Arg, G: Generator<Arg> + ?Sized> Generator<Arg> for PinBox<G> {
+    type Yield = G::Yield;
+    type Return = G::Return;
+
+    fn resume(mut self: Pin<Self>, arg: Arg) -> GeneratorState<G::Yield, G::Return> {
+        G::resume(self.as_pin(), arg)
+    }
+}
+
+/// Builds a generator from a step function called on every resume. With the
+/// `$state; |s, arg| ...` form, the step function also receives `state` pinned.
+#[macro_export]
+macro_rules! gen {
+    (|$arg:tt $(: $t:ty)?| $body:expr) => {
+        $crate::generator::from_fn((), move |_, $arg $(: $t)?| $body)
+    };
+    ($state:expr; |$s:pat, $arg:tt $(: $t:ty)?| $body:expr) => {
+        $crate::generator::from_fn($state, move |$s, $arg $(: $t)?| $body)
+    };
+}
+
+pub fn from_fn<S, Arg, Y, R, F>(state: S, f: F) -> FromFn<S, F> where
+    F: FnMut(Pin<S>, Arg) -> GeneratorState<Y, R>,
+{
+    FromFn { state, f }
+}
+
+#[derive(Debug)]
+pub struct FromFn<S, F> {
+    state: S,
+    f: F,
+}
+
+impl<S, Arg, Y, R, F> Generator<Arg> for FromFn<S, F> where
+    F: FnMut(Pin<S>, Arg) -> GeneratorState<Y, R>,
+{
+    type Yield = Y;
+    type Return = R;
+
+    fn resume(mut self: Pin<Self>, arg: Arg) -> GeneratorState<Y, R> {
+        let this = unsafe { Pin::get_mut(&mut self) };
+        (this.f)(unsafe { Pin::new_unchecked(&mut this.state) }, arg)
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn into_iter<G: Generator<Return = ()> + ?Sized>(gen: PinBox<G>) -> GenIter<G> {
+    GenIter { gen, done: false }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct GenIter<G: ?Sized> {
+    done: bool,
+    gen: PinBox<G>,
+}
+
+#[cfg(feature = "std")]
+impl<G: Generator<Return = ()> + ?Sized> Iterator for GenIter<G> {
+    type Item = G::Yield;
+
+    fn next(&mut self) -> Option<G::Yield> {
+        if self.done { return None }
+        match self.gen.as_pin().resume(()) {
+            GeneratorState::Yielded(item)   => Some(item),
+            GeneratorState::Complete(())    => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+pub fn into_stream<G: Generator<Return = ()>>(gen: G) -> GenStream<G> {
+    GenStream { gen, done: false }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct GenStream<G> {
+    gen: G,
+    done: bool,
+}
+
+impl<G> GenStream<G> {
+    unsafe_pinned!(gen: G);
+    unsafe_unpinned!(done: bool);
+}
+
+impl<G: Generator<Return = ()>> Stream for GenStream<G> {
+    type Item = G::Yield;
+
+    fn poll_next(mut self: Pin<Self>, _: &mut Context) -> Poll<Option<G::Yield>> {
+        if *self.done() { return Poll::Ready(None) }
+        match self.gen().resume(()) {
+            GeneratorState::Yielded(item)   => Poll::Ready(Some(item)),
+            GeneratorState::Complete(())    => {
+                *self.done() = true;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use boxed::PinBox;
+    use executor::block_on;
+    use mem::Pin;
+    use stream::StreamExt;
+
+    use super::{into_iter, into_stream, Generator, GeneratorState};
+
+    fn count_to(max: u32) -> impl Generator<Yield = u32, Return = ()> {
+        gen!(0u32; |mut n, ()| if *n < max {
+            *n += 1;
+            GeneratorState::Yielded(*n)
+        } else {
+            GeneratorState::Complete(())
+        })
+    }
+
+    #[test]
+    fn stateless_generator_takes_resume_arguments() {
+        let doubler = gen!(|x: u32| match x {
+            0   => GeneratorState::Complete("done"),
+            x   => GeneratorState::Yielded(x * 2),
+        });
+        pin_mut!(doubler);
+        assert_eq!(Pin::borrow(&mut doubler).resume(3), GeneratorState::Yielded(6));
+        assert_eq!(Pin::borrow(&mut doubler).resume(0), GeneratorState::Complete("done"));
+    }
+
+    #[test]
+    fn generator_as_iterator() {
+        assert_eq!(into_iter(PinBox::new(count_to(3))).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn generator_as_stream() {
+        assert_eq!(block_on(into_stream(count_to(2)).collect::<Vec<_>>()), [1, 2]);
+    }
+}
@@ -0,0 +1,150 @@
+use core::convert::Infallible;
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+use mem::Pin;
+
+/// An initializer that writes a `T` into a slot which is already at its final,
+/// pinned address.
+///
+/// # Safety
+///
+/// Implementations must either fully initialize the slot and return `Ok`, or
+/// return `Err` after dropping whatever they had already written into it.
+pub unsafe trait PinInit<T> {
+    type Error;
+
+    fn init(self, slot: Pin<MaybeUninit<T>>) -> Result<(), Self::Error>;
+}
+
+pub struct FromFn<T, F> {
+    f: F,
+    _marker: PhantomData<fn(T)>,
+}
+
+unsafe impl<T, E, F> PinInit<T> for FromFn<T, F> where
+    F: FnOnce(Pin<MaybeUninit<T>>) -> Result<(), E>
+{
+    type Error = E;
+
+    fn init(self, slot: Pin<MaybeUninit<T>>) -> Result<(), E> {
+        (self.f)(slot)
+    }
+}
+
+/// # Safety
+///
+/// `f` must uphold the contract of `PinInit`.
+pub unsafe fn from_fn<T, E, F>(f: F) -> FromFn<T, F> where
+    F: FnOnce(Pin<MaybeUninit<T>>) -> Result<(), E>
+{
+    FromFn { f, _marker: PhantomData }
+}
+
+pub struct Value<T> {
+    data: T,
+}
+
+unsafe impl<T> PinInit<T> for Value<T> {
+    type Error = Infallible;
+
+    fn init(self, mut slot: Pin<MaybeUninit<T>>) -> Result<(), Infallible> {
+        unsafe { ptr::write(Pin::get_mut(&mut slot).as_mut_ptr(), self.data) };
+        Ok(())
+    }
+}
+
+pub fn value<T>(data: T) -> Value<T> {
+    Value { data }
+}
+
+/// Drops a partially initialized field in place unless it is forgotten, so that
+/// fallible initializers can clean up after themselves on an early return.
+pub struct DropGuard<T: ?Sized> {
+    ptr: *mut T,
+}
+
+impl<T: ?Sized> DropGuard<T> {
+    /// # Safety
+    ///
+    /// `ptr` must point to an initialized value which nothing else will drop.
+    pub unsafe fn new(ptr: *mut T) -> DropGuard<T> {
+        DropGuard { ptr }
+    }
+
+    pub fn forget(this: DropGuard<T>) {
+        ::core::mem::forget(this)
+    }
+}
+
+impl<T: ?Sized> Drop for DropGuard<T> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.ptr) }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::mem::MaybeUninit;
+    use core::ptr;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use boxed::PinBox;
+    use mem::Pin;
+
+    use super::{from_fn, DropGuard, PinInit};
+
+    struct Counted(Rc<Cell<usize>>);
+
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    struct Pair {
+        first: Counted,
+        second: Counted,
+    }
+
+    fn pair(drops: &Rc<Cell<usize>>, fail: bool) -> impl PinInit<Pair, Error = &'static str> {
+        let drops = drops.clone();
+        unsafe {
+            from_fn(move |mut slot: Pin<MaybeUninit<Pair>>| {
+                let pair = Pin::get_mut(&mut slot).as_mut_ptr();
+                ptr::write(ptr::addr_of_mut!((*pair).first), Counted(drops.clone()));
+                let first = DropGuard::new(ptr::addr_of_mut!((*pair).first));
+                if fail { return Err("second field failed") }
+                ptr::write(ptr::addr_of_mut!((*pair).second), Counted(drops));
+                DropGuard::forget(first);
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn failed_init_drops_written_fields() {
+        let drops = Rc::new(Cell::new(0));
+        assert!(PinBox::try_pin_init(pair(&drops, true)).is_err());
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn successful_init_drops_nothing() {
+        let drops = Rc::new(Cell::new(0));
+        let boxed = PinBox::try_pin_init(pair(&drops, false)).unwrap();
+        assert_eq!(drops.get(), 0);
+        drop(boxed);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn failed_overwrite_drops_old_value_and_written_fields() {
+        let drops = Rc::new(Cell::new(0));
+        let boxed = PinBox::try_pin_init(pair(&drops, false)).unwrap();
+        assert!(PinBox::try_overwrite(boxed, pair(&drops, true)).is_err());
+        assert_eq!(drops.get(), 3);
+    }
+}
@@ -0,0 +1,314 @@
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr;
+
+#[cfg(feature = "nightly")]
+use iter::PinIterator;
+use marker::{Pinned, Unpin};
+use mem::Pin;
+
+/// A node type which embeds a `Link`.
+///
+/// # Safety
+///
+/// `link` must always return the same `Link`, which must be a field of `self`.
+pub unsafe trait Linked {
+    fn link(&self) -> &Link;
+}
+
+pub struct Link {
+    prev: Cell<*const Link>,
+    next: Cell<*const Link>,
+    owner: Cell<*mut ()>,
+    _pinned: Pinned,
+}
+
+impl Link {
+    pub fn new() -> Link {
+        Link {
+            prev: Cell::new(ptr::null()),
+            next: Cell::new(ptr::null()),
+            owner: Cell::new(ptr::null_mut()),
+            _pinned: Pinned,
+        }
+    }
+
+    pub fn is_linked(&self) -> bool {
+        !self.next.get().is_null()
+    }
+
+    // Links never move while linked, and every neighbor unlinks itself before
+    // it is dropped, so a linked node's neighbors are always alive.
+    fn unlink(&self) {
+        if !self.is_linked() { return }
+        unsafe {
+            (*self.prev.get()).next.set(self.next.get());
+            (*self.next.get()).prev.set(self.prev.get());
+        }
+        self.prev.set(ptr::null());
+        self.next.set(ptr::null());
+        self.owner.set(ptr::null_mut());
+    }
+
+    fn insert_after(&self, link: &Link) {
+        let next = self.next.get();
+        link.prev.set(self);
+        link.next.set(next);
+        unsafe { (*next).prev.set(link) };
+        self.next.set(link);
+    }
+}
+
+impl Default for Link {
+    fn default() -> Link {
+        Link::new()
+    }
+}
+
+impl Drop for Link {
+    fn drop(&mut self) {
+        self.unlink()
+    }
+}
+
+impl fmt::Debug for Link {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Link").field("linked", &self.is_linked()).finish()
+    }
+}
+
+// The list is circular through `head`, so it must be pinned before any node is
+// inserted. Nodes are borrowed for `'a`, so they cannot be touched by their
+// owners while they are in the list.
+pub struct List<'a, T: Linked + 'a> {
+    head: Link,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T: Linked + 'a> List<'a, T> {
+    pub fn new() -> List<'a, T> {
+        List { head: Link::new(), _marker: PhantomData }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.head.is_linked() || ptr::eq(self.head.next.get(), &self.head)
+    }
+
+    pub fn push_front(this: &mut Pin<List<'a, T>>, node: Pin<'a, T>) {
+        let link = List::prepare(this, node);
+        unsafe { this.head.insert_after(&*link) };
+    }
+
+    pub fn push_back(this: &mut Pin<List<'a, T>>, node: Pin<'a, T>) {
+        let link = List::prepare(this, node);
+        unsafe { (*this.head.prev.get()).insert_after(&*link) };
+    }
+
+    pub fn pop_front(this: &mut Pin<List<'a, T>>) -> Option<Pin<'a, T>> {
+        List::cursor(this).remove_current()
+    }
+
+    pub fn pop_back(this: &mut Pin<List<'a, T>>) -> Option<Pin<'a, T>> {
+        List::cursor_back(this).remove_current()
+    }
+
+    pub fn cursor<'c>(this: &'c mut Pin<List<'a, T>>) -> Cursor<'c, 'a, T> {
+        let mut cursor = Cursor { list: &**this, current: &this.head };
+        cursor.move_next();
+        cursor
+    }
+
+    pub fn cursor_back<'c>(this: &'c mut Pin<List<'a, T>>) -> Cursor<'c, 'a, T> {
+        let mut cursor = Cursor { list: &**this, current: &this.head };
+        cursor.move_prev();
+        cursor
+    }
+
+    fn prepare(this: &Pin<List<'a, T>>, mut node: Pin<'a, T>) -> *const Link {
+        if !this.head.is_linked() {
+            this.head.prev.set(&this.head);
+            this.head.next.set(&this.head);
+        }
+
+        let owner = unsafe { Pin::get_mut(&mut node) as *mut T };
+        unsafe {
+            let link = (*owner).link();
+            link.unlink();
+            link.owner.set(owner as *mut ());
+            link
+        }
+    }
+}
+
+impl<'a, T: Linked + 'a> Default for List<'a, T> {
+    fn default() -> List<'a, T> {
+        List::new()
+    }
+}
+
+impl<'a, T: Linked + 'a> Drop for List<'a, T> {
+    fn drop(&mut self) {
+        if !self.head.is_linked() { return }
+        while !ptr::eq(self.head.next.get(), &self.head) {
+            unsafe { (*self.head.next.get()).unlink() };
+        }
+        self.head.prev.set(ptr::null());
+        self.head.next.set(ptr::null());
+    }
+}
+
+impl<'a, T: Linked + 'a> fmt::Debug for List<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("List").field("empty", &self.is_empty()).finish()
+    }
+}
+
+/// A cursor over a `List`, which may point at a node or at the "ghost"
+/// position between the back and the front of the list.
+pub struct Cursor<'c, 'a: 'c, T: Linked + 'a> {
+    list: &'c List<'a, T>,
+    current: *const Link,
+}
+
+impl<'c, 'a: 'c, T: Linked + 'a> Cursor<'c, 'a, T> {
+    pub fn current<'b>(&'b mut self) -> Option<Pin<'b, T>> {
+        if self.is_ghost() { return None }
+        unsafe {
+            let owner = (*self.current).owner.get() as *mut T;
+            Some(Pin::new_unchecked(&mut *owner))
+        }
+    }
+
+    pub fn move_next(&mut self) {
+        if self.list.head.is_linked() {
+            self.current = unsafe { (*self.current).next.get() };
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if self.list.head.is_linked() {
+            self.current = unsafe { (*self.current).prev.get() };
+        }
+    }
+
+    pub fn remove_current(&mut self) -> Option<Pin<'a, T>> {
+        if self.is_ghost() { return None }
+        unsafe {
+            let link = &*self.current;
+            let owner = link.owner.get() as *mut T;
+            self.current = link.next.get();
+            link.unlink();
+            Some(Pin::new_unchecked(&mut *owner))
+        }
+    }
+
+    fn is_ghost(&self) -> bool {
+        ptr::eq(self.current, &self.list.head)
+    }
+}
+
+// A cursor only points at nodes, so moving it never moves them.
+#[cfg(feature = "nightly")]
+impl<'c, 'a: 'c, T: Linked + 'a> Unpin for Cursor<'c, 'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'c, 'a: 'c, T: Linked + 'a> Unpin for Cursor<'c, 'a, T> {}
+
+// Iterating yields the current node and then moves past it, stopping at the ghost.
+#[cfg(feature = "nightly")]
+impl<'c, 'a: 'c, T: Linked + 'a> PinIterator for Cursor<'c, 'a, T> {
+    type Item = T;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, T>> {
+        let cursor: &'b mut Cursor<'c, 'a, T> = &mut **self;
+        if cursor.is_ghost() { return None }
+        let current = cursor.current;
+        cursor.move_next();
+        unsafe { Some(Pin::new_unchecked(&mut *((*current).owner.get() as *mut T))) }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use mem::Pin;
+
+    use super::{Link, Linked, List};
+
+    struct Node {
+        link: Link,
+        value: u32,
+    }
+
+    unsafe impl Linked for Node {
+        fn link(&self) -> &Link {
+            &self.link
+        }
+    }
+
+    fn node(value: u32) -> Node {
+        Node { link: Link::new(), value }
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let (a, b, c) = (node(1), node(2), node(3));
+        pin_mut!(a, b, c);
+        let list = List::new();
+        pin_mut!(list);
+        assert!(list.is_empty());
+
+        List::push_back(&mut list, a);
+        List::push_back(&mut list, b);
+        List::push_front(&mut list, c);
+        assert_eq!(List::pop_front(&mut list).unwrap().value, 3);
+        assert_eq!(List::pop_back(&mut list).unwrap().value, 2);
+        assert_eq!(List::pop_back(&mut list).unwrap().value, 1);
+        assert!(List::pop_front(&mut list).is_none());
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn cursor_removes_current() {
+        let (a, b, c) = (node(1), node(2), node(3));
+        pin_mut!(a, b, c);
+        let list = List::new();
+        pin_mut!(list);
+        List::push_back(&mut list, a);
+        List::push_back(&mut list, b);
+        List::push_back(&mut list, c);
+
+        {
+            let mut cursor = List::cursor(&mut list);
+            cursor.move_next();
+            let removed = cursor.remove_current().unwrap();
+            assert_eq!(removed.value, 2);
+            assert!(!removed.link.is_linked());
+            assert_eq!(cursor.current().unwrap().value, 3);
+            cursor.move_next();
+            assert!(cursor.current().is_none());
+            cursor.move_next();
+            assert_eq!(cursor.current().unwrap().value, 1);
+        }
+
+        let mut values = vec![];
+        while let Some(node) = List::pop_front(&mut list) {
+            values.push(node.value);
+        }
+        assert_eq!(values, vec![1, 3]);
+    }
+
+    #[test]
+    fn dropping_the_list_unlinks_nodes() {
+        let a = node(1);
+        pin_mut!(a);
+        let addr = &*a as *const Node;
+        {
+            let list = List::new();
+            pin_mut!(list);
+            List::push_back(&mut list, Pin::borrow(&mut a));
+        }
+        assert!(!a.link.is_linked());
+        assert_eq!(&*a as *const Node, addr);
+    }
+}
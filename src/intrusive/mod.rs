@@ -0,0 +1,6 @@
+//! Intrusive data structures over pinned, caller-owned nodes.
+mod list;
+mod stack;
+
+pub use self::list::{Cursor, Link, Linked, List};
+pub use self::stack::{AtomicLink, AtomicLinked, Drain, Stack};
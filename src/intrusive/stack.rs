@@ -0,0 +1,234 @@
+use core::fmt;
+use core::marker::PhantomData;
+use core::ptr;
+use core::sync::atomic::AtomicPtr;
+use core::sync::atomic::Ordering::{Acquire, Relaxed, Release};
+
+use marker::Pinned;
+use mem::Pin;
+
+/// A node type which embeds an `AtomicLink`.
+///
+/// # Safety
+///
+/// `link` must always return the same `AtomicLink`, which must be a field of `self`.
+pub unsafe trait AtomicLinked {
+    fn link(&self) -> &AtomicLink;
+}
+
+pub struct AtomicLink {
+    next: AtomicPtr<AtomicLink>,
+    owner: AtomicPtr<()>,
+    _pinned: Pinned,
+}
+
+impl AtomicLink {
+    pub fn new() -> AtomicLink {
+        AtomicLink {
+            next: AtomicPtr::new(ptr::null_mut()),
+            owner: AtomicPtr::new(ptr::null_mut()),
+            _pinned: Pinned,
+        }
+    }
+}
+
+impl Default for AtomicLink {
+    fn default() -> AtomicLink {
+        AtomicLink::new()
+    }
+}
+
+impl fmt::Debug for AtomicLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("AtomicLink")
+    }
+}
+
+// A Treiber stack. Nodes are borrowed for `'a`, so a node cannot be dropped or
+// touched by its owner until it has been popped or the stack is gone.
+//
+// Any thread may push or `pop_all`, and both are lock-free. `pop_all` takes the
+// whole list with a single swap, so it never reads a node's `next` and is
+// immune to the ABA problem. `pop` does read it, so it needs `&mut self`
+// instead, which rules out ABA without tagged pointers.
+pub struct Stack<'a, T: AtomicLinked + 'a> {
+    head: AtomicPtr<AtomicLink>,
+    _marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, T: AtomicLinked + Send + 'a> Send for Stack<'a, T> {}
+unsafe impl<'a, T: AtomicLinked + Send + 'a> Sync for Stack<'a, T> {}
+
+impl<'a, T: AtomicLinked + 'a> Stack<'a, T> {
+    pub fn new() -> Stack<'a, T> {
+        Stack {
+            head: AtomicPtr::new(ptr::null_mut()),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.load(Relaxed).is_null()
+    }
+
+    pub fn push(&self, mut node: Pin<'a, T>) {
+        let owner = unsafe { Pin::get_mut(&mut node) as *mut T };
+        let link = unsafe { (*owner).link() };
+        link.owner.store(owner as *mut (), Relaxed);
+
+        let mut head = self.head.load(Relaxed);
+        loop {
+            link.next.store(head, Relaxed);
+            let new = link as *const AtomicLink as *mut AtomicLink;
+            match self.head.compare_exchange_weak(head, new, Release, Relaxed) {
+                Ok(_)       => return,
+                Err(actual) => head = actual,
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<Pin<'a, T>> {
+        let head = *self.head.get_mut();
+        if head.is_null() { return None }
+        *self.head.get_mut() = unsafe { (*head).next.load(Relaxed) };
+        unsafe { owner(head) }
+    }
+
+    pub fn pop_all(&self) -> Drain<'a, T> {
+        Drain { current: self.head.swap(ptr::null_mut(), Acquire), _marker: PhantomData }
+    }
+}
+
+impl<'a, T: AtomicLinked + 'a> Default for Stack<'a, T> {
+    fn default() -> Stack<'a, T> {
+        Stack::new()
+    }
+}
+
+impl<'a, T: AtomicLinked + 'a> fmt::Debug for Stack<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Stack").field("empty", &self.is_empty()).finish()
+    }
+}
+
+pub struct Drain<'a, T: AtomicLinked + 'a> {
+    current: *mut AtomicLink,
+    _marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, T: AtomicLinked + Send + 'a> Send for Drain<'a, T> {}
+
+impl<'a, T: AtomicLinked + 'a> Iterator for Drain<'a, T> {
+    type Item = Pin<'a, T>;
+
+    fn next(&mut self) -> Option<Pin<'a, T>> {
+        if self.current.is_null() { return None }
+        let link = self.current;
+        self.current = unsafe { (*link).next.load(Relaxed) };
+        unsafe { owner(link) }
+    }
+}
+
+unsafe fn owner<'a, T: 'a>(link: *mut AtomicLink) -> Option<Pin<'a, T>> {
+    if link.is_null() { return None }
+    let owner = (*link).owner.load(Relaxed) as *mut T;
+    Some(Pin::new_unchecked(&mut *owner))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::sync::atomic::AtomicBool;
+    use core::sync::atomic::Ordering::SeqCst;
+    use std::thread;
+
+    use mem::Pin;
+
+    use super::{AtomicLink, AtomicLinked, Stack};
+
+    struct Node {
+        link: AtomicLink,
+        taken: AtomicBool,
+    }
+
+    unsafe impl AtomicLinked for Node {
+        fn link(&self) -> &AtomicLink {
+            &self.link
+        }
+    }
+
+    fn node() -> Node {
+        Node { link: AtomicLink::new(), taken: AtomicBool::new(false) }
+    }
+
+    fn take(node: &Pin<Node>) {
+        assert!(!node.taken.swap(true, SeqCst), "node popped twice");
+    }
+
+    fn give_back<'a>(stack: &Stack<'a, Node>, node: Pin<'a, Node>) {
+        node.taken.store(false, SeqCst);
+        stack.push(node);
+    }
+
+    #[test]
+    fn push_and_pop_all() {
+        let mut nodes: Vec<Node> = (0..3).map(|_| node()).collect();
+        let addrs: Vec<*const Node> = nodes.iter().map(|node| node as *const Node).collect();
+        let mut stack = Stack::new();
+        for node in nodes.iter_mut() {
+            stack.push(unsafe { Pin::new_unchecked(node) });
+        }
+
+        let popped = stack.pop().unwrap();
+        assert_eq!(&*popped as *const Node, addrs[2]);
+        let drained: Vec<*const Node> = stack.pop_all().map(|node| &*node as *const Node).collect();
+        assert_eq!(drained, [addrs[1], addrs[0]]);
+        assert!(stack.is_empty());
+        assert!(stack.pop().is_none());
+    }
+
+    #[test]
+    fn heavy_contention() {
+        const NODES: usize = 64;
+        const THREADS: usize = 8;
+        const ROUNDS: usize = 20_000;
+
+        let mut nodes: Vec<Node> = (0..NODES).map(|_| node()).collect();
+        let mut stack = Stack::new();
+        let (held, shared) = nodes.split_at_mut(NODES / 2);
+        for node in shared {
+            stack.push(unsafe { Pin::new_unchecked(node) });
+        }
+
+        // Every thread keeps draining and refilling the stack, and half of them
+        // also push fresh nodes of their own along the way.
+        let mut held: Vec<_> = held.chunks_mut(NODES / THREADS).collect();
+        thread::scope(|scope| {
+            for id in 0..THREADS {
+                let stack = &stack;
+                let own = if id % 2 == 0 { held.pop() } else { None };
+                scope.spawn(move || {
+                    let mut own: Vec<_> = own.into_iter().flatten().map(|node| unsafe {
+                        Pin::new_unchecked(node)
+                    }).collect();
+                    for _ in 0..ROUNDS {
+                        let drained: Vec<_> = stack.pop_all().collect();
+                        drained.iter().for_each(take);
+                        for node in drained {
+                            give_back(stack, node);
+                        }
+                        if let Some(node) = own.pop() {
+                            stack.push(node);
+                        }
+                    }
+                });
+            }
+        });
+
+        let mut popped = 0;
+        while let Some(node) = stack.pop() {
+            take(&node);
+            popped += 1;
+        }
+        assert_eq!(popped, NODES);
+    }
+}
@@ -0,0 +1,179 @@
+#[cfg(feature = "std")]
+use boxed::PinBox;
+use mem::Pin;
+
+// A lending iterator: each item borrows from the pinned iterator until the next
+// call to `next`, so self-borrowing cursors can hand out pinned items.
+pub trait PinIterator {
+    type Item: ?Sized;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, Self::Item>>;
+
+    fn map<U: ?Sized, F>(self, f: F) -> Map<Self, F> where
+        F: for<'b, 'c> FnMut(&'c mut Pin<'b, Self::Item>) -> Pin<'c, U>,
+        Self: Sized,
+    {
+        Map { iter: self, f }
+    }
+
+    fn filter<F>(self, f: F) -> Filter<Self, F> where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        Filter { iter: self, f }
+    }
+
+    fn for_each<F>(self: &mut Pin<Self>, mut f: F) where
+        F: FnMut(Pin<Self::Item>),
+    {
+        while let Some(item) = self.next() {
+            f(item);
+        }
+    }
+
+    fn count(self: &mut Pin<Self>) -> usize {
+        let mut count = 0;
+        while let Some(_) = self.next() {
+            count += 1;
+        }
+        count
+    }
+}
+
+// Projected pins are temporaries, but the items they return point into the data
+// behind them, which is borrowed for `'b`. This relies on every `PinIterator`
+// handing out items that live in the iterator's referent and never in the `Pin`
+// handle `next` was called through, so stretching an item from the handle's
+// borrow to `'b` never outlives what it points to.
+fn next_projected<'b, I: PinIterator + ?Sized>(mut iter: Pin<'b, I>) -> Option<Pin<'b, I::Item>> {
+    iter.next().map(|mut item| unsafe { Pin::new_unchecked(&mut *(Pin::get_mut(&mut item) as *mut I::Item)) })
+}
+
+// A pinned slice iterates by shrinking itself from the front. A `PinBox<[T]>`
+// has no such impl, since it can neither shrink its allocation nor keep a
+// position; iterate over `PinBox::as_pin` instead.
+impl<'a, T: 'a> PinIterator for Pin<'a, [T]> {
+    type Item = T;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, T>> {
+        let slice: &mut Pin<'a, [T]> = &mut **self;
+        let (first, rest) = unsafe { &mut *(Pin::get_mut(slice) as *mut [T]) }.split_first_mut()?;
+        *slice = unsafe { Pin::new_unchecked(rest) };
+        Some(unsafe { Pin::new_unchecked(first) })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I: PinIterator + ?Sized> PinIterator for PinBox<I> {
+    type Item = I::Item;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, I::Item>> {
+        next_projected((**self).as_pin())
+    }
+}
+
+#[derive(Debug)]
+pub struct Map<I, F> {
+    iter: I,
+    f: F,
+}
+
+// `f` has the shape of the accessors generated by `unsafe_pinned!`, so a field
+// projection can be passed directly.
+impl<I: PinIterator, U: ?Sized, F> PinIterator for Map<I, F> where
+    F: for<'b, 'c> FnMut(&'c mut Pin<'b, I::Item>) -> Pin<'c, U>,
+{
+    type Item = U;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, U>> {
+        let this = unsafe { Pin::get_mut(self) };
+        let mut item = next_projected(unsafe { Pin::new_unchecked(&mut this.iter) })?;
+        let mut mapped = (this.f)(&mut item);
+        // `f` is generic over the borrow of `item`, so `U` cannot name the
+        // handle's lifetime and `mapped` must point into the item itself, which
+        // lives for `'b`, rather than into the local handle.
+        Some(unsafe { Pin::new_unchecked(&mut *(Pin::get_mut(&mut mapped) as *mut U)) })
+    }
+}
+
+#[derive(Debug)]
+pub struct Filter<I, F> {
+    iter: I,
+    f: F,
+}
+
+impl<I: PinIterator, F: FnMut(&I::Item) -> bool> PinIterator for Filter<I, F> {
+    type Item = I::Item;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, I::Item>> {
+        let this = unsafe { Pin::get_mut(self) };
+        // Rejected items are released before the next call, which the borrow
+        // checker can't see through the conditional return.
+        let iter = &mut this.iter as *mut I;
+        loop {
+            let item = next_projected(unsafe { Pin::new_unchecked(&mut *iter) })?;
+            if (this.f)(&item) { return Some(item) }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use boxed::PinBox;
+    use marker::Pinned;
+    use mem::Pin;
+
+    use super::PinIterator;
+
+    struct Item {
+        value: u32,
+        _pinned: Pinned,
+    }
+
+    impl Item {
+        unsafe_pinned!(value: u32);
+    }
+
+    fn items() -> PinBox<[Item]> {
+        let items: Vec<Item> = (1..5).map(|value| Item { value, _pinned: Pinned }).collect();
+        PinBox::from(items.into_boxed_slice())
+    }
+
+    #[test]
+    fn map_projects_items() {
+        let mut boxed = items();
+        let addr = &boxed[1].value as *const u32;
+        let mut iter = boxed.as_pin().map(Item::value);
+        let mut iter = Pin::new(&mut iter);
+        assert_eq!(iter.next().map(|value| *value), Some(1));
+        assert_eq!(iter.next().map(|value| &*value as *const u32), Some(addr));
+        assert_eq!(iter.count(), 2);
+    }
+
+    #[test]
+    fn filter_skips_rejected_items() {
+        let mut boxed = items();
+        let mut iter = boxed.as_pin().filter(|item| item.value % 2 == 0);
+        let mut iter = Pin::new(&mut iter);
+        assert_eq!(iter.next().map(|item| item.value), Some(2));
+        assert_eq!(iter.next().map(|item| item.value), Some(4));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn for_each_visits_every_item() {
+        let mut boxed = items();
+        let mut sum = 0;
+        let mut iter = boxed.as_pin();
+        Pin::new(&mut iter).for_each(|item| sum += item.value);
+        assert_eq!(sum, 10);
+    }
+
+    #[test]
+    fn count_consumes_the_iterator() {
+        let mut boxed = items();
+        let mut iter = PinBox::new(boxed.as_pin());
+        assert_eq!(iter.as_pin().count(), 4);
+        assert_eq!(iter.as_pin().count(), 0);
+    }
+}
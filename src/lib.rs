@@ -1,13 +1,39 @@
 //! Experiment with pinning self-referential structs.
-#![cfg_attr(feature = "nightly", feature(fundamental, optin_builtin_traits, coerce_unsized, unsize))]
+#![cfg_attr(feature = "nightly", feature(arbitrary_self_types, fundamental, optin_builtin_traits, coerce_unsized, dispatch_from_dyn, unsize))]
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(feature = "std")]
 extern crate core;
 
-#[cfg(feature = "nightly")]
+#[macro_use]
 pub mod marker;
-#[cfg(feature = "nightly")]
+#[macro_use]
 pub mod mem;
-#[cfg(all(feature = "nightly", feature = "std"))]
+pub mod init;
+pub mod intrusive;
+pub mod slice;
+pub mod task;
+#[cfg(feature = "nightly")]
+pub mod future;
+#[cfg(feature = "nightly")]
+pub mod stream;
+#[cfg(feature = "nightly")]
+pub mod generator;
+#[cfg(feature = "nightly")]
+pub mod iter;
+#[cfg(feature = "std")]
 pub mod boxed;
+#[cfg(feature = "std")]
+pub mod vec;
+#[cfg(feature = "std")]
+pub mod arena;
+#[cfg(feature = "std")]
+pub mod slab;
+#[cfg(feature = "std")]
+pub mod self_ref;
+#[cfg(all(feature = "nightly", feature = "std"))]
+pub mod executor;
+#[cfg(all(feature = "nightly", feature = "std"))]
+pub mod rc;
+#[cfg(all(feature = "nightly", feature = "std"))]
+pub mod sync;
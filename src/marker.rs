@@ -1 +1,142 @@
-pub auto trait Unpin { }
+use core::marker::PhantomData;
+
+#[cfg(feature = "nightly")]
+mod nightly;
+
+#[cfg(feature = "nightly")]
+pub use self::nightly::Unpin;
+
+/// # Safety
+///
+/// Only implement this for types which can be moved after being pinned, for
+/// example because they never hand out pinned references to their contents.
+#[cfg(not(feature = "nightly"))]
+pub unsafe trait Unpin { }
+
+/// A marker which makes the types containing it `!Unpin`:
+///
+/// ```compile_fail
+/// use pin_api::marker::{Pinned, Unpin};
+///
+/// fn assert_unpin<T: Unpin>() {}
+/// assert_unpin::<(u32, Pinned)>();
+/// ```
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pinned;
+
+#[cfg(not(feature = "nightly"))]
+#[macro_export]
+macro_rules! impl_unpin {
+    (impl<$($p:ident),*> $t:ty) => {
+        unsafe impl<$($p: $crate::marker::Unpin),*> $crate::marker::Unpin for $t {}
+    };
+    ($($t:ty),*) => {
+        $(unsafe impl $crate::marker::Unpin for $t {})*
+    };
+}
+
+// Used by `#[derive(PinProject)]`, which can't see which form `Unpin` takes.
+#[cfg(feature = "nightly")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_unpin {
+    ($($t:tt)*) => { impl $($t)* };
+}
+
+#[cfg(not(feature = "nightly"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __impl_unpin {
+    ($($t:tt)*) => { unsafe impl $($t)* };
+}
+
+#[cfg(not(feature = "nightly"))]
+mod impls {
+    use core::cell::{Cell, RefCell, UnsafeCell};
+    use core::marker::PhantomData;
+
+    use super::Unpin;
+
+    impl_unpin!(
+        (), bool, char, str,
+        u8, u16, u32, u64, usize,
+        i8, i16, i32, i64, isize,
+        f32, f64
+    );
+
+    unsafe impl<T: ?Sized + Unpin> Unpin for &T {}
+    unsafe impl<T: ?Sized + Unpin> Unpin for &mut T {}
+    unsafe impl<T: ?Sized> Unpin for *const T {}
+    unsafe impl<T: ?Sized> Unpin for *mut T {}
+    unsafe impl<T: ?Sized + Unpin> Unpin for PhantomData<T> {}
+    unsafe impl<T: Unpin> Unpin for [T] {}
+
+    impl_unpin!(impl<T> Option<T>);
+    impl_unpin!(impl<T, E> Result<T, E>);
+    impl_unpin!(impl<T> Cell<T>);
+    impl_unpin!(impl<T> RefCell<T>);
+    impl_unpin!(impl<T> UnsafeCell<T>);
+
+    impl_unpin!(impl<A> (A,));
+    impl_unpin!(impl<A, B> (A, B));
+    impl_unpin!(impl<A, B, C> (A, B, C));
+    impl_unpin!(impl<A, B, C, D> (A, B, C, D));
+    impl_unpin!(impl<A, B, C, D, E> (A, B, C, D, E));
+    impl_unpin!(impl<A, B, C, D, E, F> (A, B, C, D, E, F));
+
+    macro_rules! array_impls {
+        ($($n:expr)*) => {
+            $(impl_unpin!(impl<T> [T; $n]);)*
+        }
+    }
+
+    array_impls!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+
+    #[cfg(feature = "std")]
+    mod std_impls {
+        use std::collections::{BTreeMap, HashMap, VecDeque};
+        use std::rc::Rc;
+        use std::sync::Arc;
+
+        use super::super::Unpin;
+
+        unsafe impl<T: ?Sized + Unpin> Unpin for Box<T> {}
+        unsafe impl<T: ?Sized + Unpin> Unpin for Rc<T> {}
+        unsafe impl<T: ?Sized + Unpin> Unpin for Arc<T> {}
+
+        impl_unpin!(String);
+        impl_unpin!(impl<T> Vec<T>);
+        impl_unpin!(impl<T> VecDeque<T>);
+        impl_unpin!(impl<K, V> BTreeMap<K, V>);
+        impl_unpin!(impl<K, V, S> HashMap<K, V, S>);
+    }
+}
+
+#[doc(hidden)]
+pub struct __Wrapper<'a, T: ?Sized>(PhantomData<&'a ()>, T);
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T: ?Sized + Unpin> Unpin for __Wrapper<'a, T> {}
+
+#[cfg(all(test, not(feature = "nightly")))]
+mod tests {
+    use core::marker::PhantomData;
+
+    use super::Unpin;
+
+    fn assert_unpin<T: Unpin + ?Sized>() {}
+
+    struct Plain;
+    struct Generic<T>(PhantomData<T>);
+
+    impl_unpin!(Plain);
+    impl_unpin!(impl<T> Generic<T>);
+
+    #[test]
+    fn impl_unpin() {
+        assert_unpin::<Plain>();
+        assert_unpin::<Generic<Plain>>();
+        assert_unpin::<(u32, Option<Generic<u8>>, [Plain; 2])>();
+    }
+}
+
@@ -0,0 +1,6 @@
+// Kept out of `marker.rs` so that stable compilers never parse this syntax.
+use super::Pinned;
+
+pub auto trait Unpin { }
+
+impl !Unpin for Pinned {}
@@ -1,10 +1,14 @@
 use core::fmt;
+use core::mem;
+#[cfg(feature = "nightly")]
 use core::marker::Unsize;
-use core::ops::{CoerceUnsized, Deref, DerefMut};
+use core::ops::{Deref, DerefMut};
+#[cfg(feature = "nightly")]
+use core::ops::{CoerceUnsized, DispatchFromDyn};
 
 use marker::Unpin;
 
-#[fundamental]
+#[cfg_attr(feature = "nightly", fundamental)]
 pub struct Pin<'a, T: ?Sized + 'a> {
     inner: &'a mut T,
 }
@@ -16,6 +20,9 @@ impl<'a, T: ?Sized + Unpin> Pin<'a, T> {
 }
 
 impl<'a, T: ?Sized> Pin<'a, T> {
+    /// # Safety
+    ///
+    /// The referent must never be moved again, even after `'a` ends.
     pub unsafe fn new_unchecked(reference: &'a mut T) -> Pin<'a, T> {
         Pin { inner: reference }
     }
@@ -24,15 +31,140 @@ impl<'a, T: ?Sized> Pin<'a, T> {
         Pin { inner: this.inner }
     }
 
+    /// # Safety
+    ///
+    /// The referent must not be moved out of the returned reference.
     pub unsafe fn get_mut<'b>(this: &'b mut Pin<'a, T>) -> &'b mut T {
         this.inner
     }
 
+    /// # Safety
+    ///
+    /// `f` must return a field of its argument which is structurally pinned,
+    /// and must not move out of its argument.
     pub unsafe fn map<'b, U, F>(this: &'b mut Pin<'a, T>, f: F) -> Pin<'b, U> where
         F: FnOnce(&mut T) -> &mut U
     {
         Pin { inner: f(this.inner) }
     }
+
+    pub fn into_ref(this: Pin<'a, T>) -> PinRef<'a, T> {
+        PinRef { inner: this.inner }
+    }
+
+    #[doc(hidden)]
+    pub unsafe fn __into_dyn<U, F>(this: Pin<'a, T>, f: F) -> Pin<'a, U> where
+        U: ?Sized + 'a,
+        F: FnOnce(*mut T) -> *mut U,
+    {
+        Pin { inner: &mut *f(this.inner) }
+    }
+}
+
+impl<'a, T> Pin<'a, T> {
+    // The old value is dropped in place before the new one is written, so
+    // nothing pinned is ever moved.
+    pub fn set(this: &mut Pin<'a, T>, value: T) {
+        *this.inner = value;
+    }
+}
+
+impl<'a, T: Unpin> Pin<'a, T> {
+    pub fn replace_with<F: FnOnce(&mut T) -> T>(this: &mut Pin<'a, T>, f: F) -> T {
+        let value = f(this.inner);
+        mem::replace(this.inner, value)
+    }
+}
+
+impl<'a, T> Pin<'a, Option<T>> {
+    pub fn as_pin_option(this: Pin<'a, Option<T>>) -> Option<Pin<'a, T>> {
+        this.inner.as_mut().map(|inner| Pin { inner })
+    }
+
+    pub fn set_none(this: &mut Pin<'a, Option<T>>) {
+        *this.inner = None;
+    }
+}
+
+impl<'a, T, E> Pin<'a, Result<T, E>> {
+    pub fn as_pin_result(this: Pin<'a, Result<T, E>>) -> Result<Pin<'a, T>, Pin<'a, E>> {
+        match *this.inner {
+            Ok(ref mut inner)   => Ok(Pin { inner }),
+            Err(ref mut inner)  => Err(Pin { inner }),
+        }
+    }
+}
+
+pub fn with_pinned<T, R, F>(mut data: T, f: F) -> R where
+    F: for<'a> FnOnce(Pin<'a, T>) -> R
+{
+    f(unsafe { Pin::new_unchecked(&mut data) })
+}
+
+#[macro_export]
+macro_rules! pin_mut {
+    ($($x:ident),*) => { $(
+        let mut $x = $x;
+        #[allow(unused_mut)]
+        let mut $x = unsafe { $crate::mem::Pin::new_unchecked(&mut $x) };
+    )* }
+}
+
+#[macro_export]
+macro_rules! pin_into_dyn {
+    ($pin:expr => $t:ty) => {
+        match $pin {
+            pin => unsafe { $crate::mem::Pin::__into_dyn(pin, |ptr| -> *mut $t { ptr }) },
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+pub trait PinnedDrop {
+    /// # Safety
+    ///
+    /// Only the `Drop` impl generated by `pinned_drop!` or
+    /// `#[pin_project(PinnedDrop)]` may call this, exactly once.
+    unsafe fn drop(self: Pin<Self>);
+}
+
+#[cfg(feature = "nightly")]
+#[macro_export]
+macro_rules! pinned_drop {
+    (impl<$($p:ident),*> $t:ty) => {
+        impl<$($p),*> Drop for $t {
+            fn drop(&mut self) {
+                unsafe { $crate::mem::PinnedDrop::drop($crate::mem::Pin::new_unchecked(self)) }
+            }
+        }
+    };
+    ($t:ty) => {
+        impl Drop for $t {
+            fn drop(&mut self) {
+                unsafe { $crate::mem::PinnedDrop::drop($crate::mem::Pin::new_unchecked(self)) }
+            }
+        }
+    };
+}
+
+#[cfg(feature = "nightly")]
+#[macro_export]
+macro_rules! unsafe_pinned {
+    ($f:tt : $t:ty) => {
+        fn $f<'__a>(self: &'__a mut $crate::mem::Pin<Self>) -> $crate::mem::Pin<'__a, $t> {
+            unsafe { $crate::mem::Pin::map(self, |x| &mut x.$f) }
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+#[macro_export]
+macro_rules! unsafe_unpinned {
+    ($f:tt : $t:ty) => {
+        fn $f<'__a>(self: &'__a mut $crate::mem::Pin<Self>) -> &'__a mut $t {
+            unsafe { &mut $crate::mem::Pin::get_mut(self).$f }
+        }
+    }
 }
 
 impl<'a, T: ?Sized> Deref for Pin<'a, T> {
@@ -67,4 +199,236 @@ impl<'a, T: ?Sized> fmt::Pointer for Pin<'a, T> {
     }
 }
 
+#[cfg(feature = "nightly")]
 impl<'a, T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Pin<'a, U>> for Pin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Pin<'a, U>> for Pin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T: ?Sized> Unpin for Pin<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T: ?Sized> Unpin for Pin<'a, T> {}
+
+#[cfg_attr(feature = "nightly", fundamental)]
+pub struct PinRef<'a, T: ?Sized + 'a> {
+    inner: &'a T,
+}
+
+impl<'a, T: ?Sized + Unpin> PinRef<'a, T> {
+    pub fn new(reference: &'a T) -> PinRef<'a, T> {
+        PinRef { inner: reference }
+    }
+}
+
+impl<'a, T: ?Sized> PinRef<'a, T> {
+    /// # Safety
+    ///
+    /// The referent must never be moved again, even after `'a` ends.
+    pub unsafe fn new_unchecked(reference: &'a T) -> PinRef<'a, T> {
+        PinRef { inner: reference }
+    }
+
+    pub fn get_ref(this: PinRef<'a, T>) -> &'a T {
+        this.inner
+    }
+
+    /// # Safety
+    ///
+    /// `f` must return a field of its argument which is structurally pinned.
+    pub unsafe fn map<U: ?Sized, F>(this: PinRef<'a, T>, f: F) -> PinRef<'a, U> where
+        F: FnOnce(&T) -> &U
+    {
+        PinRef { inner: f(this.inner) }
+    }
+}
+
+impl<'a, T: ?Sized> Clone for PinRef<'a, T> {
+    fn clone(&self) -> PinRef<'a, T> {
+        *self
+    }
+}
+
+impl<'a, T: ?Sized> Copy for PinRef<'a, T> {}
+
+impl<'a, T: ?Sized> Deref for PinRef<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.inner
+    }
+}
+
+impl<'a, T: fmt::Debug + ?Sized> fmt::Debug for PinRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<'a, T: fmt::Display + ?Sized> fmt::Display for PinRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<'a, T: ?Sized> fmt::Pointer for PinRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Pointer::fmt(&(self.inner as *const T), f)
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<PinRef<'a, U>> for PinRef<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T: ?Sized> Unpin for PinRef<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T: ?Sized> Unpin for PinRef<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use marker::Pinned;
+
+    use super::{with_pinned, Pin};
+
+    #[test]
+    fn pin_mut_pins_in_place() {
+        let data = (1u32, Pinned);
+        pin_mut!(data);
+        let addr = &*data as *const (u32, Pinned);
+        assert_eq!(&*Pin::borrow(&mut data) as *const (u32, Pinned), addr);
+        assert_eq!(data.0, 1);
+    }
+
+    #[test]
+    fn with_pinned_returns_closure_result() {
+        assert_eq!(with_pinned((2u32, Pinned), |pinned| pinned.0 * 2), 4);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn pinned_drop_runs_once() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        use super::PinnedDrop;
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Counted(Pinned);
+
+        impl PinnedDrop for Counted {
+            unsafe fn drop(self: Pin<Self>) {
+                DROPS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        pinned_drop!(Counted);
+
+        with_pinned(Counted(Pinned), |_| ());
+        assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "nightly")]
+    #[test]
+    fn projection_macros() {
+        struct Pair {
+            pinned: (u32, Pinned),
+            unpinned: u32,
+        }
+
+        impl Pair {
+            unsafe_pinned!(pinned: (u32, Pinned));
+            unsafe_unpinned!(unpinned: u32);
+        }
+
+        with_pinned(Pair { pinned: (1, Pinned), unpinned: 0 }, |mut pair| {
+            let addr = &pair.pinned as *const (u32, Pinned);
+            assert_eq!(&*pair.pinned() as *const (u32, Pinned), addr);
+            *pair.unpinned() += 1;
+            assert_eq!(pair.unpinned, 1);
+        });
+    }
+
+    #[test]
+    fn pin_ref() {
+        use super::PinRef;
+
+        let value = 3u32;
+        let shared = PinRef::new(&value);
+        let copy = shared;
+        assert_eq!(*shared + *copy, 6);
+        assert!(core::ptr::eq(PinRef::get_ref(copy), &value));
+
+        with_pinned((4u32, Pinned), |pinned| {
+            let addr = &*pinned as *const (u32, Pinned);
+            let shared = Pin::into_ref(pinned);
+            let field = unsafe { PinRef::map(shared, |pair| &pair.0) };
+            assert_eq!(*field, 4);
+            assert!(core::ptr::eq(PinRef::get_ref(shared), addr));
+        });
+    }
+
+    #[test]
+    fn option_and_result_projections() {
+        let some = Some((5u32, Pinned));
+        pin_mut!(some);
+        let addr = some.as_ref().unwrap() as *const (u32, Pinned);
+        let inner = Pin::as_pin_option(some).unwrap();
+        assert_eq!(&*inner as *const (u32, Pinned), addr);
+        assert!(with_pinned(None::<Pinned>, |pinned| Pin::as_pin_option(pinned).is_none()));
+
+        let project = |pinned: Pin<Result<u32, u8>>| match Pin::as_pin_result(pinned) {
+            Ok(pin)     => Ok(*pin),
+            Err(pin)    => Err(*pin),
+        };
+        assert_eq!(with_pinned(Ok(1), project), Ok(1));
+        assert_eq!(with_pinned(Err(2), project), Err(2));
+    }
+
+    #[test]
+    fn set_none_drops_in_place() {
+        use core::cell::Cell;
+
+        struct Flag<'a>(&'a Cell<bool>, Pinned);
+
+        impl<'a> Drop for Flag<'a> {
+            fn drop(&mut self) {
+                self.0.set(true);
+            }
+        }
+
+        let dropped = Cell::new(false);
+        with_pinned(Some(Flag(&dropped, Pinned)), |mut pinned| {
+            Pin::set_none(&mut pinned);
+            assert!(dropped.get());
+            assert!(pinned.is_none());
+        });
+    }
+
+    #[test]
+    fn set_and_replace_with() {
+        let data = (1u32, Pinned);
+        pin_mut!(data);
+        let addr = &*data as *const (u32, Pinned);
+        Pin::set(&mut data, (2, Pinned));
+        assert_eq!((data.0, &*data as *const (u32, Pinned)), (2, addr));
+
+        with_pinned(3u32, |mut pinned| {
+            assert_eq!(Pin::replace_with(&mut pinned, |old| *old + 1), 3);
+            assert_eq!(*pinned, 4);
+        });
+    }
+
+    #[test]
+    fn pin_into_dyn() {
+        use core::fmt::Debug;
+
+        with_pinned((6u32, Pinned), |pinned| {
+            let addr = &*pinned as *const (u32, Pinned) as *const ();
+            let dynamic = pin_into_dyn!(pinned => dyn Debug);
+            assert_eq!(&*dynamic as *const dyn Debug as *const (), addr);
+        });
+    }
+}
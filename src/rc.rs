@@ -0,0 +1,133 @@
+use core::fmt;
+use core::marker::Unsize;
+use core::ops::{CoerceUnsized, Deref};
+use std::rc::{self, Rc};
+
+use marker::Unpin;
+use mem::Pin;
+
+#[fundamental]
+pub struct PinRc<T: ?Sized> {
+    inner: Rc<T>,
+}
+
+pub struct Weak<T: ?Sized> {
+    inner: rc::Weak<T>,
+}
+
+impl<T> PinRc<T> {
+    pub fn new(data: T) -> PinRc<T> {
+        PinRc { inner: Rc::new(data) }
+    }
+}
+
+impl<T: ?Sized> PinRc<T> {
+    pub fn as_pin<'a>(this: &'a mut PinRc<T>) -> Option<Pin<'a, T>> {
+        Rc::get_mut(&mut this.inner).map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub unsafe fn get_mut<'a>(this: &'a mut PinRc<T>) -> Option<&'a mut T> {
+        Rc::get_mut(&mut this.inner)
+    }
+
+    pub unsafe fn unpin(this: PinRc<T>) -> Rc<T> {
+        this.inner
+    }
+
+    pub fn downgrade(this: &PinRc<T>) -> Weak<T> {
+        Weak { inner: Rc::downgrade(&this.inner) }
+    }
+
+    pub fn strong_count(this: &PinRc<T>) -> usize {
+        Rc::strong_count(&this.inner)
+    }
+
+    pub fn weak_count(this: &PinRc<T>) -> usize {
+        Rc::weak_count(&this.inner)
+    }
+
+    pub fn ptr_eq(this: &PinRc<T>, other: &PinRc<T>) -> bool {
+        Rc::ptr_eq(&this.inner, &other.inner)
+    }
+}
+
+impl<T: ?Sized> Clone for PinRc<T> {
+    fn clone(&self) -> PinRc<T> {
+        PinRc { inner: self.inner.clone() }
+    }
+}
+
+impl<T: ?Sized> Deref for PinRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &*self.inner
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for PinRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for PinRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for PinRc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ptr: *const T = &*self.inner;
+        fmt::Pointer::fmt(&ptr, f)
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<PinRc<U>> for PinRc<T> {}
+
+impl<T: ?Sized> Unpin for PinRc<T> {}
+
+impl<T: ?Sized> Weak<T> {
+    pub fn upgrade(&self) -> Option<PinRc<T>> {
+        self.inner.upgrade().map(|inner| PinRc { inner })
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Weak<T> {
+        Weak { inner: self.inner.clone() }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Weak<U>> for Weak<T> {}
+
+#[cfg(test)]
+mod tests {
+    use marker::Pinned;
+
+    use super::PinRc;
+
+    #[test]
+    fn pinned_address_is_stable() {
+        let mut pinned = PinRc::new((0u32, Pinned));
+        let addr = &*pinned as *const (u32, Pinned);
+        let clone = pinned.clone();
+        assert!(PinRc::as_pin(&mut pinned).is_none());
+        drop(clone);
+
+        let mut moved = vec![pinned];
+        assert_eq!(&*PinRc::as_pin(&mut moved[0]).unwrap() as *const (u32, Pinned), addr);
+
+        let weak = PinRc::downgrade(&moved[0]);
+        let moved = moved.pop().unwrap();
+        assert_eq!(&*weak.upgrade().unwrap() as *const (u32, Pinned), addr);
+        assert_eq!(&*moved as *const (u32, Pinned), addr);
+    }
+}
@@ -0,0 +1,186 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::NonNull;
+
+/// A family of dependent types, one for each lifetime of the owner borrow.
+///
+/// ```
+/// use pin_api::self_ref::{Dependent, SelfRef};
+///
+/// struct WordsFamily;
+///
+/// impl<'a> Dependent<'a> for WordsFamily {
+///     type Type = Vec<&'a str>;
+/// }
+///
+/// let words = SelfRef::<String, WordsFamily>::new("a b c".to_owned(), |s| s.split(' ').collect());
+/// assert_eq!(words.with_dependent(|_, words| words.len()), 3);
+/// ```
+pub trait Dependent<'a> {
+    type Type: 'a;
+}
+
+// The owner is boxed and held as a raw pointer, so it never moves even when the
+// `SelfRef` does. It is not kept in a `PinBox`: moving a `Box` asserts unique
+// access to its contents, which the dependent's borrows would violate. `Drop`
+// drops the dependent before freeing the owner.
+pub struct SelfRef<O: 'static, D: for<'a> Dependent<'a>> {
+    dependent: ManuallyDrop<<D as Dependent<'static>>::Type>,
+    owner: NonNull<O>,
+}
+
+unsafe impl<O, D> Send for SelfRef<O, D> where
+    O: Send + 'static,
+    D: for<'a> Dependent<'a>,
+    <D as Dependent<'static>>::Type: Send,
+{}
+
+unsafe impl<O, D> Sync for SelfRef<O, D> where
+    O: Sync + 'static,
+    D: for<'a> Dependent<'a>,
+    <D as Dependent<'static>>::Type: Sync,
+{}
+
+impl<O: 'static, D: for<'a> Dependent<'a>> SelfRef<O, D> {
+    pub fn new<F>(owner: O, f: F) -> SelfRef<O, D> where
+        F: for<'a> FnOnce(&'a O) -> <D as Dependent<'a>>::Type
+    {
+        match SelfRef::try_new(owner, |owner| Ok::<_, Infallible>(f(owner))) {
+            Ok(this)    => this,
+            Err(never)  => match never {},
+        }
+    }
+
+    pub fn try_new<E, F>(owner: O, f: F) -> Result<SelfRef<O, D>, E> where
+        F: for<'a> FnOnce(&'a O) -> Result<<D as Dependent<'a>>::Type, E>
+    {
+        let guard = OwnerGuard(NonNull::from(Box::leak(Box::new(owner))));
+        let dependent = f(unsafe { &*guard.0.as_ptr() })?;
+        let owner = guard.0;
+        mem::forget(guard);
+        Ok(SelfRef { dependent: ManuallyDrop::new(dependent), owner })
+    }
+
+    pub fn owner(&self) -> &O {
+        unsafe { self.owner.as_ref() }
+    }
+
+    // The dependent is only handed out under a universally quantified lifetime,
+    // so nothing shorter lived than the owner can be stored into it.
+    pub fn with_dependent<'s, R, F>(&'s self, f: F) -> R where
+        F: for<'a> FnOnce(&'a O, &'a <D as Dependent<'a>>::Type) -> R
+    {
+        let dependent = &*self.dependent as *const _ as *const <D as Dependent<'s>>::Type;
+        f(self.owner(), unsafe { &*dependent })
+    }
+
+    pub fn with_dependent_mut<'s, R, F>(&'s mut self, f: F) -> R where
+        F: for<'a> FnOnce(&'a O, &'a mut <D as Dependent<'a>>::Type) -> R
+    {
+        let dependent = &mut *self.dependent as *mut _ as *mut <D as Dependent<'s>>::Type;
+        f(self.owner(), unsafe { &mut *dependent })
+    }
+
+    pub fn into_owner(self) -> O {
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            ManuallyDrop::drop(&mut this.dependent);
+            *Box::from_raw(this.owner.as_ptr())
+        }
+    }
+}
+
+impl<O: 'static, D: for<'a> Dependent<'a>> Drop for SelfRef<O, D> {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.dependent);
+            drop(Box::from_raw(self.owner.as_ptr()));
+        }
+    }
+}
+
+// Frees the owner if the dependent could not be built, including on panic.
+struct OwnerGuard<O>(NonNull<O>);
+
+impl<O> Drop for OwnerGuard<O> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) })
+    }
+}
+
+impl<O: fmt::Debug + 'static, D: for<'a> Dependent<'a>> fmt::Debug for SelfRef<O, D> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SelfRef").field("owner", self.owner()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::{Dependent, SelfRef};
+
+    struct Owner(Rc<RefCell<Vec<&'static str>>>);
+
+    impl Drop for Owner {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push("owner");
+        }
+    }
+
+    struct Borrower<'a>(&'a Owner);
+
+    impl<'a> Drop for Borrower<'a> {
+        fn drop(&mut self) {
+            (self.0).0.borrow_mut().push("dependent");
+        }
+    }
+
+    struct BorrowerFamily;
+
+    impl<'a> Dependent<'a> for BorrowerFamily {
+        type Type = Borrower<'a>;
+    }
+
+    #[test]
+    fn dependent_is_dropped_before_owner() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let this = SelfRef::<Owner, BorrowerFamily>::new(Owner(log.clone()), |owner| Borrower(owner));
+        let moved = this;
+        moved.with_dependent(|owner, dependent| assert!(::core::ptr::eq(owner, dependent.0)));
+        drop(moved);
+        assert_eq!(*log.borrow(), ["dependent", "owner"]);
+    }
+
+    #[test]
+    fn into_owner_drops_dependent() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let this = SelfRef::<Owner, BorrowerFamily>::new(Owner(log.clone()), |owner| Borrower(owner));
+        let owner = this.into_owner();
+        assert_eq!(*log.borrow(), ["dependent"]);
+        drop(owner);
+        assert_eq!(*log.borrow(), ["dependent", "owner"]);
+    }
+
+    #[test]
+    fn try_new_drops_owner_on_error() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let result = SelfRef::<Owner, BorrowerFamily>::try_new(Owner(log.clone()), |_| Err(()));
+        assert!(result.is_err());
+        assert_eq!(*log.borrow(), ["owner"]);
+    }
+
+    #[test]
+    fn try_new_drops_owner_on_panic() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            SelfRef::<Owner, BorrowerFamily>::new(Owner(log.clone()), |_| panic!("dependent failed"))
+        }));
+        assert!(result.is_err());
+        assert_eq!(*log.borrow(), ["owner"]);
+    }
+}
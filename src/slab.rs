@@ -0,0 +1,191 @@
+use core::fmt;
+
+#[cfg(feature = "nightly")]
+use iter::PinIterator;
+use marker::Unpin;
+use mem::Pin;
+use vec::{self, PinVec};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    index: usize,
+    generation: u64,
+}
+
+struct Slot<T> {
+    generation: u64,
+    data: Option<T>,
+}
+
+// Removing a value bumps its slot's generation, which invalidates its keys.
+pub struct PinSlab<T> {
+    slots: PinVec<Slot<T>>,
+    vacant: Vec<usize>,
+    len: usize,
+}
+
+impl<T> PinSlab<T> {
+    pub fn new() -> PinSlab<T> {
+        PinSlab { slots: PinVec::new(), vacant: Vec::new(), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, data: T) -> Key {
+        self.len += 1;
+        match self.vacant.pop() {
+            Some(index) => {
+                let slot = unsafe { self.slot_mut(index) };
+                slot.data = Some(data);
+                Key { index, generation: slot.generation }
+            }
+            None        => {
+                let index = self.slots.push(Slot { generation: 0, data: Some(data) });
+                Key { index, generation: 0 }
+            }
+        }
+    }
+
+    pub fn contains(&self, key: Key) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn get(&self, key: Key) -> Option<&T> {
+        match self.slots.get(key.index) {
+            Some(slot) if slot.generation == key.generation => slot.data.as_ref(),
+            _                                               => None,
+        }
+    }
+
+    pub fn get_pin<'a>(&'a mut self, key: Key) -> Option<Pin<'a, T>> {
+        if !self.contains(key) { return None }
+        let slot = unsafe { self.slot_mut(key.index) };
+        slot.data.as_mut().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub fn iter_pin<'a>(&'a mut self) -> IterPin<'a, T> {
+        IterPin { slots: self.slots.iter_pin(), index: 0 }
+    }
+
+    pub fn remove(&mut self, key: Key) -> bool {
+        if !self.contains(key) { return false }
+        {
+            let slot = unsafe { self.slot_mut(key.index) };
+            slot.data = None;
+            slot.generation += 1;
+        }
+        self.vacant.push(key.index);
+        self.len -= 1;
+        true
+    }
+
+    // The caller must not move the value out of the returned slot.
+    unsafe fn slot_mut(&mut self, index: usize) -> &mut Slot<T> {
+        let mut slot = self.slots.get_pin(index).unwrap();
+        &mut *(Pin::get_mut(&mut slot) as *mut Slot<T>)
+    }
+}
+
+impl<T> Default for PinSlab<T> {
+    fn default() -> PinSlab<T> {
+        PinSlab::new()
+    }
+}
+
+impl<T> fmt::Debug for PinSlab<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PinSlab").field("len", &self.len).finish()
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<T: Unpin> Unpin for PinSlab<T> {}
+
+pub struct IterPin<'a, T: 'a> {
+    slots: vec::IterPin<'a, Slot<T>>,
+    index: usize,
+}
+
+impl<'a, T> Iterator for IterPin<'a, T> {
+    type Item = (Key, Pin<'a, T>);
+
+    fn next(&mut self) -> Option<(Key, Pin<'a, T>)> {
+        loop {
+            let mut slot = self.slots.next()?;
+            let index = self.index;
+            self.index += 1;
+            let slot = unsafe { &mut *(Pin::get_mut(&mut slot) as *mut Slot<T>) };
+            if let Some(ref mut data) = slot.data {
+                let key = Key { index, generation: slot.generation };
+                return Some((key, unsafe { Pin::new_unchecked(data) }))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> PinIterator for IterPin<'a, T> {
+    type Item = T;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, T>> {
+        Iterator::next(&mut **self).map(|(_, data)| data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use marker::Pinned;
+
+    use super::PinSlab;
+
+    #[test]
+    fn stale_keys_are_rejected() {
+        let mut slab = PinSlab::new();
+        let first = slab.insert(1);
+        assert!(slab.remove(first));
+        assert!(!slab.remove(first));
+
+        let second = slab.insert(2);
+        assert!(!slab.contains(first));
+        assert!(slab.get(first).is_none());
+        assert!(slab.get_pin(first).is_none());
+        assert_eq!(slab.get(second), Some(&2));
+        assert_eq!(slab.len(), 1);
+    }
+
+    #[test]
+    fn remove_drops_in_place() {
+        struct Counted<'a>(&'a Cell<usize>, Pinned);
+
+        impl<'a> Drop for Counted<'a> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut slab = PinSlab::new();
+        let keys: Vec<_> = (0..10).map(|_| slab.insert(Counted(&drops, Pinned))).collect();
+        let addr = &*slab.get_pin(keys[9]).unwrap() as *const Counted;
+        slab.remove(keys[3]);
+        assert_eq!(drops.get(), 1);
+        assert_eq!(&*slab.get_pin(keys[9]).unwrap() as *const Counted, addr);
+        assert_eq!(slab.iter_pin().count(), 9);
+        drop(slab);
+        assert_eq!(drops.get(), 10);
+    }
+}
@@ -0,0 +1,180 @@
+use core::slice;
+
+#[cfg(feature = "nightly")]
+use iter::PinIterator;
+use marker::Unpin;
+use mem::Pin;
+
+// Unwraps a pin for its whole lifetime; the caller must not move out of it.
+unsafe fn into_mut<'a, T: ?Sized>(mut this: Pin<'a, T>) -> &'a mut T {
+    &mut *(Pin::get_mut(&mut this) as *mut T)
+}
+
+// Implemented for slices and arrays, so the pinned slice operations on `Pin`
+// work on both.
+pub trait PinSlice {
+    type Item;
+
+    fn as_slice_pin<'a>(this: Pin<'a, Self>) -> Pin<'a, [Self::Item]>;
+}
+
+impl<T> PinSlice for [T] {
+    type Item = T;
+
+    fn as_slice_pin<'a>(this: Pin<'a, [T]>) -> Pin<'a, [T]> {
+        this
+    }
+}
+
+macro_rules! array_impls {
+    ($($n:expr)*) => { $(
+        impl<T> PinSlice for [T; $n] {
+            type Item = T;
+
+            fn as_slice_pin<'a>(this: Pin<'a, [T; $n]>) -> Pin<'a, [T]> {
+                unsafe { Pin::new_unchecked(&mut into_mut(this)[..]) }
+            }
+        }
+    )* }
+}
+
+array_impls!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+
+/// The two halves returned by `Pin::split_at_pin`.
+pub type SplitPin<'a, T> = (Pin<'a, [T]>, Pin<'a, [T]>);
+
+impl<'a, S: PinSlice + ?Sized> Pin<'a, S> {
+    pub fn get_pin<'b>(this: &'b mut Pin<'a, S>, index: usize) -> Option<Pin<'b, S::Item>> {
+        let slice = unsafe { into_mut(S::as_slice_pin(Pin::borrow(this))) };
+        slice.get_mut(index).map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub fn first_pin<'b>(this: &'b mut Pin<'a, S>) -> Option<Pin<'b, S::Item>> {
+        let slice = unsafe { into_mut(S::as_slice_pin(Pin::borrow(this))) };
+        slice.first_mut().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub fn last_pin<'b>(this: &'b mut Pin<'a, S>) -> Option<Pin<'b, S::Item>> {
+        let slice = unsafe { into_mut(S::as_slice_pin(Pin::borrow(this))) };
+        slice.last_mut().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub fn iter_pin(this: Pin<'a, S>) -> IterPin<'a, S::Item> {
+        IterPin { inner: unsafe { into_mut(S::as_slice_pin(this)) }.iter_mut() }
+    }
+
+    pub fn split_at_pin(this: Pin<'a, S>, mid: usize) -> SplitPin<'a, S::Item> {
+        let (left, right) = unsafe { into_mut(S::as_slice_pin(this)) }.split_at_mut(mid);
+        unsafe { (Pin::new_unchecked(left), Pin::new_unchecked(right)) }
+    }
+
+    pub fn chunks_pin(this: Pin<'a, S>, size: usize) -> ChunksPin<'a, S::Item> {
+        ChunksPin { inner: unsafe { into_mut(S::as_slice_pin(this)) }.chunks_mut(size) }
+    }
+}
+
+#[derive(Debug)]
+pub struct IterPin<'a, T: 'a> {
+    inner: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterPin<'a, T> {
+    type Item = Pin<'a, T>;
+
+    fn next(&mut self) -> Option<Pin<'a, T>> {
+        self.inner.next().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterPin<'a, T> {
+    fn next_back(&mut self) -> Option<Pin<'a, T>> {
+        self.inner.next_back().map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+}
+
+impl<'a, T> ExactSizeIterator for IterPin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> PinIterator for IterPin<'a, T> {
+    type Item = T;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, T>> {
+        Iterator::next(&mut **self)
+    }
+}
+
+#[derive(Debug)]
+pub struct ChunksPin<'a, T: 'a> {
+    inner: slice::ChunksMut<'a, T>,
+}
+
+impl<'a, T> Iterator for ChunksPin<'a, T> {
+    type Item = Pin<'a, [T]>;
+
+    fn next(&mut self) -> Option<Pin<'a, [T]>> {
+        self.inner.next().map(|chunk| unsafe { Pin::new_unchecked(chunk) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for ChunksPin<'a, T> {
+    fn next_back(&mut self) -> Option<Pin<'a, [T]>> {
+        self.inner.next_back().map(|chunk| unsafe { Pin::new_unchecked(chunk) })
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> Unpin for ChunksPin<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T> Unpin for ChunksPin<'a, T> {}
+
+#[cfg(test)]
+mod tests {
+    use marker::Pinned;
+    use mem::Pin;
+
+    #[test]
+    fn element_access() {
+        let array = [(1u32, Pinned), (2, Pinned), (3, Pinned)];
+        pin_mut!(array);
+        let addr = &array[1] as *const (u32, Pinned);
+        assert_eq!(Pin::get_pin(&mut array, 1).map(|pin| &*pin as *const (u32, Pinned)), Some(addr));
+        assert!(Pin::get_pin(&mut array, 3).is_none());
+        assert_eq!(Pin::first_pin(&mut array).map(|pin| pin.0), Some(1));
+        assert_eq!(Pin::last_pin(&mut array).map(|pin| pin.0), Some(3));
+    }
+
+    #[test]
+    fn split_and_chunk() {
+        let array = [0u32, 1, 2, 3, 4];
+        pin_mut!(array);
+        let (left, right) = Pin::split_at_pin(Pin::borrow(&mut array), 2);
+        assert_eq!((&*left, &*right), (&[0, 1][..], &[2, 3, 4][..]));
+
+        let chunks = Pin::chunks_pin(Pin::borrow(&mut array), 2);
+        assert!(chunks.map(|chunk| chunk.len()).eq([2, 2, 1].iter().cloned()));
+    }
+
+    #[test]
+    fn iter_pin_in_both_directions() {
+        let array = [0u32, 1, 2];
+        pin_mut!(array);
+        let iter = Pin::iter_pin(Pin::borrow(&mut array));
+        assert_eq!(iter.len(), 3);
+        assert!(iter.rev().map(|pin| *pin).eq([2, 1, 0].iter().cloned()));
+    }
+}
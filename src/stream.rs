@@ -0,0 +1,466 @@
+use core::mem;
+
+#[cfg(feature = "std")]
+use boxed::PinBox;
+#[cfg(feature = "std")]
+use executor;
+use future::Future;
+use marker::Unpin;
+use mem::Pin;
+#[cfg(feature = "std")]
+use slab::{Key, PinSlab};
+use task::{Context, Poll};
+
+pub trait Stream {
+    type Item;
+
+    fn poll_next(self: Pin<Self>, cx: &mut Context) -> Poll<Option<Self::Item>>;
+}
+
+impl<'a, S: Stream + ?Sized> Stream for Pin<'a, S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        S::poll_next(Pin::borrow(&mut *self), cx)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Stream + ?Sized> Stream for PinBox<S> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        S::poll_next(self.as_pin(), cx)
+    }
+}
+
+pub trait StreamExt: Stream {
+    fn next<'a>(&'a mut self) -> Next<'a, Self> where
+        Self: Unpin,
+    {
+        Next { stream: self }
+    }
+
+    fn map<U, F>(self, f: F) -> Map<Self, F> where
+        F: FnMut(Self::Item) -> U,
+        Self: Sized,
+    {
+        Map { stream: self, f }
+    }
+
+    fn filter<F>(self, f: F) -> Filter<Self, F> where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        Filter { stream: self, f }
+    }
+
+    fn take_while<F>(self, f: F) -> TakeWhile<Self, F> where
+        F: FnMut(&Self::Item) -> bool,
+        Self: Sized,
+    {
+        TakeWhile { stream: self, f, done: false }
+    }
+
+    fn zip<S: Stream>(self, other: S) -> Zip<Self, S> where
+        Self: Sized,
+    {
+        Zip { a: self, b: other, queued_a: None, queued_b: None, done: false }
+    }
+
+    fn fold<T, F>(self, init: T, f: F) -> Fold<Self, T, F> where
+        F: FnMut(T, Self::Item) -> T,
+        Self: Sized,
+    {
+        Fold { stream: self, f, acc: Some(init) }
+    }
+
+    fn collect<C>(self) -> Collect<Self, C> where
+        C: Default + Extend<Self::Item>,
+        Self: Sized,
+    {
+        Collect { stream: self, collection: Some(C::default()) }
+    }
+
+    #[cfg(feature = "std")]
+    fn buffer_unordered(self, max: usize) -> BufferUnordered<Self> where
+        Self::Item: Future,
+        Self: Sized,
+    {
+        assert!(max > 0, "buffer_unordered needs room for at least one future");
+        BufferUnordered { stream: self, done: false, futures: PinSlab::new(), keys: Vec::new(), max }
+    }
+
+    #[cfg(feature = "std")]
+    fn into_blocking_iter(self) -> BlockingIter<Self> where
+        Self: Sized,
+    {
+        BlockingIter { stream: PinBox::new(self) }
+    }
+}
+
+impl<S: Stream + ?Sized> StreamExt for S {}
+
+pub fn iter<I: IntoIterator>(iter: I) -> Iter<I::IntoIter> {
+    Iter { iter: iter.into_iter() }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Iter<I> {
+    iter: I,
+}
+
+impl<I> Iter<I> {
+    unsafe_unpinned!(iter: I);
+}
+
+impl<I: Iterator> Stream for Iter<I> {
+    type Item = I::Item;
+
+    fn poll_next(mut self: Pin<Self>, _: &mut Context) -> Poll<Option<I::Item>> {
+        Poll::Ready(self.iter().next())
+    }
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BlockingIter<S: ?Sized> {
+    stream: PinBox<S>,
+}
+
+#[cfg(feature = "std")]
+impl<S: Stream + ?Sized> Iterator for BlockingIter<S> {
+    type Item = S::Item;
+
+    fn next(&mut self) -> Option<S::Item> {
+        executor::block_on(self.stream.next())
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Next<'a, S: ?Sized + 'a> {
+    stream: &'a mut S,
+}
+
+impl<'a, S: Stream + Unpin + ?Sized> Future for Next<'a, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Map<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> Map<S, F> {
+    unsafe_pinned!(stream: S);
+    unsafe_unpinned!(f: F);
+}
+
+impl<U, S: Stream, F: FnMut(S::Item) -> U> Stream for Map<S, F> {
+    type Item = U;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<U>> {
+        match self.stream().poll_next(cx) {
+            Poll::Ready(item)   => Poll::Ready(item.map(self.f())),
+            Poll::Pending       => Poll::Pending,
+        }
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Filter<S, F> {
+    stream: S,
+    f: F,
+}
+
+impl<S, F> Filter<S, F> {
+    unsafe_pinned!(stream: S);
+    unsafe_unpinned!(f: F);
+}
+
+impl<S: Stream, F: FnMut(&S::Item) -> bool> Stream for Filter<S, F> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        loop {
+            match self.stream().poll_next(cx) {
+                Poll::Ready(Some(item)) => if (self.f())(&item) { return Poll::Ready(Some(item)) },
+                Poll::Ready(None)       => return Poll::Ready(None),
+                Poll::Pending           => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct TakeWhile<S, F> {
+    stream: S,
+    f: F,
+    done: bool,
+}
+
+impl<S, F> TakeWhile<S, F> {
+    unsafe_pinned!(stream: S);
+    unsafe_unpinned!(f: F);
+    unsafe_unpinned!(done: bool);
+}
+
+impl<S: Stream, F: FnMut(&S::Item) -> bool> Stream for TakeWhile<S, F> {
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<S::Item>> {
+        if *self.done() { return Poll::Ready(None) }
+        match self.stream().poll_next(cx) {
+            Poll::Ready(Some(item)) => {
+                if (self.f())(&item) { return Poll::Ready(Some(item)) }
+                *self.done() = true;
+                Poll::Ready(None)
+            }
+            Poll::Ready(None)       => {
+                *self.done() = true;
+                Poll::Ready(None)
+            }
+            Poll::Pending           => Poll::Pending,
+        }
+    }
+}
+
+#[must_use = "streams do nothing unless polled"]
+#[derive(Debug)]
+pub struct Zip<A: Stream, B: Stream> {
+    a: A,
+    b: B,
+    queued_a: Option<Option<A::Item>>,
+    queued_b: Option<Option<B::Item>>,
+    done: bool,
+}
+
+impl<A: Stream, B: Stream> Zip<A, B> {
+    unsafe_pinned!(a: A);
+    unsafe_pinned!(b: B);
+    unsafe_unpinned!(queued_a: Option<Option<A::Item>>);
+    unsafe_unpinned!(queued_b: Option<Option<B::Item>>);
+    unsafe_unpinned!(done: bool);
+}
+
+impl<A: Stream, B: Stream> Stream for Zip<A, B> {
+    type Item = (A::Item, B::Item);
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<(A::Item, B::Item)>> {
+        if *self.done() { return Poll::Ready(None) }
+        if self.queued_a().is_none() {
+            if let Poll::Ready(item) = self.a().poll_next(cx) {
+                *self.queued_a() = Some(item);
+            }
+        }
+        if self.queued_b().is_none() {
+            if let Poll::Ready(item) = self.b().poll_next(cx) {
+                *self.queued_b() = Some(item);
+            }
+        }
+
+        match (self.queued_a().take(), self.queued_b().take()) {
+            (Some(Some(a)), Some(Some(b)))  => Poll::Ready(Some((a, b))),
+            (Some(None), _) | (_, Some(None)) => {
+                *self.done() = true;
+                Poll::Ready(None)
+            }
+            (a, b)                          => {
+                *self.queued_a() = a;
+                *self.queued_b() = b;
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Fold<S, T, F> {
+    stream: S,
+    f: F,
+    acc: Option<T>,
+}
+
+impl<S, T, F> Fold<S, T, F> {
+    unsafe_pinned!(stream: S);
+    unsafe_unpinned!(f: F);
+    unsafe_unpinned!(acc: Option<T>);
+}
+
+impl<S: Stream, T, F: FnMut(T, S::Item) -> T> Future for Fold<S, T, F> {
+    type Output = T;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<T> {
+        loop {
+            match self.stream().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    let acc = self.acc().take().expect("Fold polled after completion");
+                    let acc = (self.f())(acc, item);
+                    *self.acc() = Some(acc);
+                }
+                Poll::Ready(None)       => {
+                    return Poll::Ready(self.acc().take().expect("Fold polled after completion"))
+                }
+                Poll::Pending           => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[must_use = "futures do nothing unless polled"]
+#[derive(Debug)]
+pub struct Collect<S, C> {
+    stream: S,
+    collection: Option<C>,
+}
+
+impl<S, C> Collect<S, C> {
+    unsafe_pinned!(stream: S);
+    unsafe_unpinned!(collection: Option<C>);
+}
+
+impl<S: Stream, C: Default + Extend<S::Item>> Future for Collect<S, C> {
+    type Output = C;
+
+    fn poll(mut self: Pin<Self>, cx: &mut Context) -> Poll<C> {
+        loop {
+            match self.stream().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    self.collection().as_mut().expect("Collect polled after completion")
+                        .extend(Some(item));
+                }
+                Poll::Ready(None)       => {
+                    let collection = mem::replace(self.collection(), None);
+                    return Poll::Ready(collection.expect("Collect polled after completion"))
+                }
+                Poll::Pending           => return Poll::Pending,
+            }
+        }
+    }
+}
+
+// In-flight futures live in a `PinSlab`, so they stay put while they are
+// polled and are dropped in place as soon as they complete.
+#[cfg(feature = "std")]
+#[must_use = "streams do nothing unless polled"]
+pub struct BufferUnordered<S: Stream> where S::Item: Future {
+    stream: S,
+    done: bool,
+    futures: PinSlab<S::Item>,
+    keys: Vec<Key>,
+    max: usize,
+}
+
+#[cfg(feature = "std")]
+impl<S: Stream> Stream for BufferUnordered<S> where S::Item: Future {
+    type Item = <S::Item as Future>::Output;
+
+    fn poll_next(mut self: Pin<Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = unsafe { Pin::get_mut(&mut self) };
+
+        while !this.done && this.keys.len() < this.max {
+            match unsafe { Pin::new_unchecked(&mut this.stream) }.poll_next(cx) {
+                Poll::Ready(Some(future))   => this.keys.push(this.futures.insert(future)),
+                Poll::Ready(None)           => this.done = true,
+                Poll::Pending               => break,
+            }
+        }
+
+        for i in 0..this.keys.len() {
+            let key = this.keys[i];
+            if let Poll::Ready(output) = this.futures.get_pin(key).unwrap().poll(cx) {
+                this.futures.remove(key);
+                this.keys.swap_remove(i);
+                return Poll::Ready(Some(output))
+            }
+        }
+
+        if this.done && this.keys.is_empty() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use executor::block_on;
+    use executor::test_futures::YieldNow;
+    use future::FutureExt;
+    use mem::Pin;
+    use task::{Context, Poll};
+
+    use super::{iter, Stream, StreamExt};
+
+    #[test]
+    fn map_filter_collect() {
+        let stream = iter(1..10).map(|x| x * 2).filter(|x| x % 3 == 0);
+        assert_eq!(block_on(stream.collect::<Vec<_>>()), [6, 12, 18]);
+    }
+
+    #[test]
+    fn take_while_stops_at_the_first_rejected_item() {
+        let stream = iter(vec![1, 2, 5, 1]).take_while(|x| *x < 3);
+        assert_eq!(block_on(stream.collect::<Vec<_>>()), [1, 2]);
+    }
+
+    #[test]
+    fn zip_ends_with_the_shorter_stream() {
+        let stream = iter(0..3).zip(iter("abcd".chars()));
+        assert_eq!(block_on(stream.collect::<Vec<_>>()), [(0, 'a'), (1, 'b'), (2, 'c')]);
+    }
+
+    // Panics if polled again after it has ended.
+    struct Once(Option<Option<u32>>);
+
+    impl Stream for Once {
+        type Item = u32;
+
+        fn poll_next(mut self: Pin<Self>, _: &mut Context) -> Poll<Option<u32>> {
+            Poll::Ready(self.0.take().expect("polled after the end"))
+        }
+    }
+
+    #[test]
+    fn zip_stays_finished() {
+        let stream = iter(0..5).zip(Once(Some(None)));
+        pin_mut!(stream);
+        assert_eq!(block_on(stream.next()), None);
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[test]
+    fn fold_and_next() {
+        assert_eq!(block_on(iter(1..5).fold(0, |acc, x| acc + x)), 10);
+
+        let mut stream = iter(0..2);
+        assert_eq!(block_on(stream.next()), Some(0));
+        assert_eq!(block_on(stream.next()), Some(1));
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[test]
+    fn buffer_unordered_yields_outputs_as_they_complete() {
+        let futures = || iter(vec![4, 0, 1]).map(|n| YieldNow(n).map(move |()| n));
+        assert_eq!(block_on(futures().buffer_unordered(3).collect::<Vec<_>>()), [0, 1, 4]);
+        assert_eq!(block_on(futures().buffer_unordered(1).collect::<Vec<_>>()), [4, 0, 1]);
+    }
+
+    #[test]
+    fn into_blocking_iter() {
+        let stream = iter(0..3).map(|n| n + 1);
+        assert_eq!(stream.into_blocking_iter().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+}
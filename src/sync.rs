@@ -0,0 +1,133 @@
+use core::fmt;
+use core::marker::Unsize;
+use core::ops::{CoerceUnsized, Deref};
+use std::sync::{self, Arc};
+
+use marker::Unpin;
+use mem::Pin;
+
+#[fundamental]
+pub struct PinArc<T: ?Sized> {
+    inner: Arc<T>,
+}
+
+pub struct Weak<T: ?Sized> {
+    inner: sync::Weak<T>,
+}
+
+impl<T> PinArc<T> {
+    pub fn new(data: T) -> PinArc<T> {
+        PinArc { inner: Arc::new(data) }
+    }
+}
+
+impl<T: ?Sized> PinArc<T> {
+    pub fn as_pin<'a>(this: &'a mut PinArc<T>) -> Option<Pin<'a, T>> {
+        Arc::get_mut(&mut this.inner).map(|data| unsafe { Pin::new_unchecked(data) })
+    }
+
+    pub unsafe fn get_mut<'a>(this: &'a mut PinArc<T>) -> Option<&'a mut T> {
+        Arc::get_mut(&mut this.inner)
+    }
+
+    pub unsafe fn unpin(this: PinArc<T>) -> Arc<T> {
+        this.inner
+    }
+
+    pub fn downgrade(this: &PinArc<T>) -> Weak<T> {
+        Weak { inner: Arc::downgrade(&this.inner) }
+    }
+
+    pub fn strong_count(this: &PinArc<T>) -> usize {
+        Arc::strong_count(&this.inner)
+    }
+
+    pub fn weak_count(this: &PinArc<T>) -> usize {
+        Arc::weak_count(&this.inner)
+    }
+
+    pub fn ptr_eq(this: &PinArc<T>, other: &PinArc<T>) -> bool {
+        Arc::ptr_eq(&this.inner, &other.inner)
+    }
+}
+
+impl<T: ?Sized> Clone for PinArc<T> {
+    fn clone(&self) -> PinArc<T> {
+        PinArc { inner: self.inner.clone() }
+    }
+}
+
+impl<T: ?Sized> Deref for PinArc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &*self.inner
+    }
+}
+
+impl<T: fmt::Display + ?Sized> fmt::Display for PinArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for PinArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.inner, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for PinArc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ptr: *const T = &*self.inner;
+        fmt::Pointer::fmt(&ptr, f)
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<PinArc<U>> for PinArc<T> {}
+
+impl<T: ?Sized> Unpin for PinArc<T> {}
+
+impl<T: ?Sized> Weak<T> {
+    pub fn upgrade(&self) -> Option<PinArc<T>> {
+        self.inner.upgrade().map(|inner| PinArc { inner })
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Weak<T> {
+        Weak { inner: self.inner.clone() }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Weak<U>> for Weak<T> {}
+
+#[cfg(test)]
+mod tests {
+    use marker::Pinned;
+
+    use super::PinArc;
+
+    #[test]
+    fn pinned_address_is_stable() {
+        let mut pinned = PinArc::new((0u32, Pinned));
+        let addr = &*pinned as *const (u32, Pinned);
+        let clone = pinned.clone();
+        assert!(PinArc::as_pin(&mut pinned).is_none());
+        drop(clone);
+
+        let mut moved = vec![pinned];
+        assert_eq!(&*PinArc::as_pin(&mut moved[0]).unwrap() as *const (u32, Pinned), addr);
+
+        let weak = PinArc::downgrade(&moved[0]);
+        let moved = moved.pop().unwrap();
+        assert_eq!(&*weak.upgrade().unwrap() as *const (u32, Pinned), addr);
+        assert_eq!(&*moved as *const (u32, Pinned), addr);
+    }
+}
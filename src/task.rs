@@ -0,0 +1,270 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+use core::mem;
+use core::ptr;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Poll<T> {
+    Ready(T),
+    Pending,
+}
+
+impl<T> Poll<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Poll<U> {
+        match self {
+            Poll::Ready(data)   => Poll::Ready(f(data)),
+            Poll::Pending       => Poll::Pending,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        match *self {
+            Poll::Ready(_)  => true,
+            Poll::Pending   => false,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.is_ready()
+    }
+}
+
+impl<T> From<T> for Poll<T> {
+    fn from(data: T) -> Poll<T> {
+        Poll::Ready(data)
+    }
+}
+
+pub struct Context<'a> {
+    waker: &'a Waker,
+}
+
+impl<'a> Context<'a> {
+    pub fn from_waker(waker: &'a Waker) -> Context<'a> {
+        Context { waker }
+    }
+
+    pub fn waker(&self) -> &'a Waker {
+        self.waker
+    }
+}
+
+impl<'a> fmt::Debug for Context<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Context").field("waker", self.waker).finish()
+    }
+}
+
+#[derive(Debug)]
+pub struct RawWaker {
+    data: *const (),
+    vtable: &'static RawWakerVTable,
+}
+
+impl RawWaker {
+    pub fn new(data: *const (), vtable: &'static RawWakerVTable) -> RawWaker {
+        RawWaker { data, vtable }
+    }
+}
+
+/// The functions behind a `RawWaker`. Each is passed the waker's data pointer;
+/// `wake` and `drop` consume it, `clone` and `wake_by_ref` do not.
+#[derive(Debug, Copy, Clone)]
+pub struct RawWakerVTable {
+    clone: unsafe fn(*const ()) -> RawWaker,
+    wake: unsafe fn(*const ()),
+    wake_by_ref: unsafe fn(*const ()),
+    drop: unsafe fn(*const ()),
+}
+
+impl RawWakerVTable {
+    pub const fn new(
+        clone: unsafe fn(*const ()) -> RawWaker,
+        wake: unsafe fn(*const ()),
+        wake_by_ref: unsafe fn(*const ()),
+        drop: unsafe fn(*const ()),
+    ) -> RawWakerVTable {
+        RawWakerVTable { clone, wake, wake_by_ref, drop }
+    }
+}
+
+pub struct Waker {
+    waker: RawWaker,
+}
+
+unsafe impl Send for Waker {}
+unsafe impl Sync for Waker {}
+
+impl Waker {
+    /// # Safety
+    ///
+    /// The functions in the vtable must uphold the contract documented on
+    /// `RawWakerVTable` for `waker`'s data pointer, and the waker must be safe
+    /// to send and share across threads.
+    pub unsafe fn from_raw(waker: RawWaker) -> Waker {
+        Waker { waker }
+    }
+
+    pub fn wake(self) {
+        let wake = self.waker.vtable.wake;
+        let data = self.waker.data;
+        mem::forget(self);
+        unsafe { wake(data) }
+    }
+
+    pub fn wake_by_ref(&self) {
+        unsafe { (self.waker.vtable.wake_by_ref)(self.waker.data) }
+    }
+
+    /// Returns `true` if both wakers are known to wake the same task.
+    ///
+    /// Vtables are compared by address, which is best-effort: a vtable held in
+    /// a `const`, such as the generic one behind `waker`, may be duplicated, so
+    /// equivalent wakers can compare unequal. Different wakers never compare
+    /// equal.
+    pub fn will_wake(&self, other: &Waker) -> bool {
+        self.waker.data == other.waker.data && ptr::eq(self.waker.vtable, other.waker.vtable)
+    }
+}
+
+impl Clone for Waker {
+    fn clone(&self) -> Waker {
+        Waker { waker: unsafe { (self.waker.vtable.clone)(self.waker.data) } }
+    }
+}
+
+impl Drop for Waker {
+    fn drop(&mut self) {
+        unsafe { (self.waker.vtable.drop)(self.waker.data) }
+    }
+}
+
+impl fmt::Debug for Waker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Waker").field("data", &self.waker.data).finish()
+    }
+}
+
+static NOOP_VTABLE: RawWakerVTable = RawWakerVTable::new(noop_clone, noop, noop, noop);
+
+static NOOP_WAKER: Waker = Waker {
+    waker: RawWaker { data: ptr::null(), vtable: &NOOP_VTABLE },
+};
+
+unsafe fn noop_clone(_: *const ()) -> RawWaker {
+    RawWaker::new(ptr::null(), &NOOP_VTABLE)
+}
+
+unsafe fn noop(_: *const ()) {}
+
+pub fn noop_waker() -> Waker {
+    NOOP_WAKER.clone()
+}
+
+pub fn noop_waker_ref() -> &'static Waker {
+    &NOOP_WAKER
+}
+
+#[cfg(feature = "std")]
+pub trait Wake: Send + Sync {
+    fn wake(self: Arc<Self>);
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.clone().wake()
+    }
+}
+
+#[cfg(feature = "std")]
+pub fn waker<W: Wake + 'static>(wake: Arc<W>) -> Waker {
+    let raw = RawWaker::new(Arc::into_raw(wake) as *const (), &ArcWaker::<W>::VTABLE);
+    unsafe { Waker::from_raw(raw) }
+}
+
+#[cfg(feature = "std")]
+struct ArcWaker<W>(PhantomData<W>);
+
+#[cfg(feature = "std")]
+impl<W: Wake + 'static> ArcWaker<W> {
+    const VTABLE: RawWakerVTable = RawWakerVTable::new(
+        ArcWaker::<W>::clone,
+        ArcWaker::<W>::wake,
+        ArcWaker::<W>::wake_by_ref,
+        ArcWaker::<W>::drop,
+    );
+
+    unsafe fn clone(data: *const ()) -> RawWaker {
+        let wake = Arc::from_raw(data as *const W);
+        mem::forget(wake.clone());
+        RawWaker::new(Arc::into_raw(wake) as *const (), &ArcWaker::<W>::VTABLE)
+    }
+
+    unsafe fn wake(data: *const ()) {
+        Wake::wake(Arc::from_raw(data as *const W))
+    }
+
+    unsafe fn wake_by_ref(data: *const ()) {
+        let wake = Arc::from_raw(data as *const W);
+        Wake::wake_by_ref(&wake);
+        mem::forget(wake);
+    }
+
+    unsafe fn drop(data: *const ()) {
+        drop(Arc::from_raw(data as *const W))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{noop_waker, noop_waker_ref, Context, Poll};
+
+    #[test]
+    fn poll_map() {
+        assert_eq!(Poll::Ready(1).map(|x| x + 1), Poll::Ready(2));
+        assert_eq!(Poll::<u32>::Pending.map(|x| x + 1), Poll::Pending);
+        assert!(Poll::from(()).is_ready());
+        assert!(Poll::<()>::Pending.is_pending());
+    }
+
+    #[test]
+    fn noop_wakers_will_wake_each_other() {
+        let waker = noop_waker();
+        let cx = Context::from_waker(&waker);
+        assert!(cx.waker().will_wake(noop_waker_ref()));
+        waker.wake_by_ref();
+        waker.clone().wake();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn arc_waker_counts_references() {
+        use core::sync::atomic::AtomicUsize;
+        use core::sync::atomic::Ordering::SeqCst;
+        use std::sync::Arc;
+
+        use super::{waker, Wake};
+
+        struct Counter(AtomicUsize);
+
+        impl Wake for Counter {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, SeqCst);
+            }
+        }
+
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let first = waker(counter.clone());
+        let second = first.clone();
+        assert!(!first.will_wake(noop_waker_ref()));
+        assert_eq!(Arc::strong_count(&counter), 3);
+
+        first.wake_by_ref();
+        first.wake();
+        assert_eq!(Arc::strong_count(&counter), 2);
+        drop(second);
+        assert_eq!(Arc::strong_count(&counter), 1);
+        assert_eq!(counter.0.load(SeqCst), 2);
+    }
+}
@@ -0,0 +1,201 @@
+use core::fmt;
+use core::ops::Index;
+use core::slice;
+
+#[cfg(feature = "nightly")]
+use iter::PinIterator;
+use marker::Unpin;
+use mem::Pin;
+
+const FIRST_CHUNK: usize = 8;
+
+// Elements are stored in chunks which are allocated with a fixed capacity and
+// never grow past it, so pushing never relocates an element once it is stored.
+pub struct PinVec<T> {
+    chunks: Vec<Vec<T>>,
+    len: usize,
+}
+
+fn locate(index: usize) -> (usize, usize) {
+    let shifted = index + FIRST_CHUNK;
+    let bits = (0usize.leading_zeros() - shifted.leading_zeros()) as usize;
+    let first_bits = (0usize.leading_zeros() - FIRST_CHUNK.leading_zeros()) as usize;
+    let chunk = bits - first_bits;
+    (chunk, shifted - (FIRST_CHUNK << chunk))
+}
+
+impl<T> PinVec<T> {
+    pub fn new() -> PinVec<T> {
+        PinVec { chunks: Vec::new(), len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push(&mut self, data: T) -> usize {
+        let (chunk, _) = locate(self.len);
+        if chunk == self.chunks.len() {
+            self.chunks.push(Vec::with_capacity(FIRST_CHUNK << chunk));
+        }
+        self.chunks[chunk].push(data);
+        self.len += 1;
+        self.len - 1
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len { return None }
+        let (chunk, offset) = locate(index);
+        Some(&self.chunks[chunk][offset])
+    }
+
+    pub fn get_pin<'a>(&'a mut self, index: usize) -> Option<Pin<'a, T>> {
+        if index >= self.len { return None }
+        let (chunk, offset) = locate(index);
+        Some(unsafe { Pin::new_unchecked(&mut self.chunks[chunk][offset]) })
+    }
+
+    pub fn iter<'a>(&'a self) -> Iter<'a, T> {
+        Iter { chunks: self.chunks.iter(), current: [].iter() }
+    }
+
+    pub fn iter_pin<'a>(&'a mut self) -> IterPin<'a, T> {
+        IterPin { chunks: self.chunks.iter_mut(), current: [].iter_mut() }
+    }
+
+    pub fn clear(&mut self) {
+        // Reset `len` first, so a panicking `Drop` can't leave it out of sync.
+        self.len = 0;
+        self.chunks.clear();
+    }
+}
+
+impl<T> Default for PinVec<T> {
+    fn default() -> PinVec<T> {
+        PinVec::new()
+    }
+}
+
+impl<T> Index<usize> for PinVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("PinVec index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PinVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<T: Unpin> Unpin for PinVec<T> {}
+
+pub struct Iter<'a, T: 'a> {
+    chunks: slice::Iter<'a, Vec<T>>,
+    current: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        loop {
+            if let Some(data) = self.current.next() { return Some(data) }
+            self.current = self.chunks.next()?.iter();
+        }
+    }
+}
+
+pub struct IterPin<'a, T: 'a> {
+    chunks: slice::IterMut<'a, Vec<T>>,
+    current: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterPin<'a, T> {
+    type Item = Pin<'a, T>;
+
+    fn next(&mut self) -> Option<Pin<'a, T>> {
+        loop {
+            if let Some(data) = self.current.next() {
+                return Some(unsafe { Pin::new_unchecked(data) })
+            }
+            self.current = self.chunks.next()?.iter_mut();
+        }
+    }
+}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(not(feature = "nightly"))]
+unsafe impl<'a, T> Unpin for IterPin<'a, T> {}
+
+#[cfg(feature = "nightly")]
+impl<'a, T> PinIterator for IterPin<'a, T> {
+    type Item = T;
+
+    fn next<'b>(self: &'b mut Pin<Self>) -> Option<Pin<'b, T>> {
+        Iterator::next(&mut **self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use marker::Pinned;
+
+    use super::PinVec;
+
+    #[test]
+    fn push_never_moves_elements() {
+        let mut vec = PinVec::new();
+        vec.push((0usize, Pinned));
+        let first = &*vec.get_pin(0).unwrap() as *const (usize, Pinned);
+        for i in 1..1000 {
+            assert_eq!(vec.push((i, Pinned)), i);
+        }
+        assert_eq!(&*vec.get_pin(0).unwrap() as *const (usize, Pinned), first);
+        assert_eq!(vec.len(), 1000);
+        assert_eq!(vec[999].0, 999);
+        assert!(vec.get(1000).is_none());
+    }
+
+    #[test]
+    fn iterates_in_order() {
+        let mut vec = PinVec::new();
+        for i in 0..100 {
+            vec.push(i);
+        }
+        assert!(vec.iter().cloned().eq(0..100));
+        assert!(vec.iter_pin().map(|pin| *pin).eq(0..100));
+        vec.clear();
+        assert!(vec.is_empty());
+        assert_eq!(vec.iter().count(), 0);
+    }
+
+    #[test]
+    fn clear_stays_consistent_when_drop_panics() {
+        use std::panic::{self, AssertUnwindSafe};
+
+        struct PanicOnDrop(bool);
+
+        impl Drop for PanicOnDrop {
+            fn drop(&mut self) {
+                if self.0 { panic!("drop panicked") }
+            }
+        }
+
+        let mut vec = PinVec::new();
+        vec.push(PanicOnDrop(true));
+        assert!(panic::catch_unwind(AssertUnwindSafe(|| vec.clear())).is_err());
+        assert!(vec.is_empty());
+        assert!(vec.get(0).is_none());
+        assert_eq!(vec.push(PanicOnDrop(false)), 0);
+    }
+}